@@ -0,0 +1,15 @@
+fn main() {
+    // Target triple of this build; only build scripts see `TARGET`, so forward it.
+    let target = std::env::var("TARGET").unwrap_or_default();
+    println!("cargo:rustc-env=MANATAN_BUILD_TARGET_COMPILED={target}");
+
+    // Commit the binary was built from. CI sets `GITHUB_SHA`; local builds may pass
+    // `MANATAN_GIT_COMMIT` explicitly. Empty when neither is set.
+    println!("cargo:rerun-if-env-changed=MANATAN_GIT_COMMIT");
+    println!("cargo:rerun-if-env-changed=GITHUB_SHA");
+    let commit = std::env::var("MANATAN_GIT_COMMIT")
+        .or_else(|_| std::env::var("GITHUB_SHA"))
+        .map(|value| value.trim().to_string())
+        .unwrap_or_default();
+    println!("cargo:rustc-env=MANATAN_GIT_COMMIT_COMPILED={commit}");
+}
@@ -1,4 +1,4 @@
-#[cfg(feature = "embed-jre")]
+#[cfg(any(feature = "embed-jre", test))]
 use std::io::{self, Cursor};
 use std::{
     fs::{self, File},
@@ -6,7 +6,7 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use tracing::info;
+use tracing::{info, warn};
 
 #[cfg(feature = "embed-jre")]
 static JRE_BYTES: &[u8] = include_bytes!("../../../bin/manatan/resources/jre_bundle.zip");
@@ -27,6 +27,26 @@ pub fn extract_file(dir: &Path, name: &str, bytes: &[u8]) -> std::io::Result<Pat
     Ok(path)
 }
 
+/// Extracts `name` unless the file on disk already holds exactly `bytes`, so an unchanged file is
+/// left alone while a missing, truncated (e.g. quarantined by antivirus) or outdated one is
+/// rewritten.
+pub fn ensure_extracted(dir: &Path, name: &str, bytes: &[u8]) -> std::io::Result<PathBuf> {
+    let path = dir.join(name);
+    let size_matches = fs::metadata(&path)
+        .map(|meta| meta.is_file() && meta.len() == bytes.len() as u64)
+        .unwrap_or(false);
+    if size_matches && fs::read(&path).is_ok_and(|existing| existing == bytes) {
+        return Ok(path);
+    }
+    if path.exists() {
+        warn!(
+            "⚠️ {} is damaged or out of date (possibly quarantined by antivirus). Re-extracting...",
+            path.display()
+        );
+    }
+    extract_file(dir, name, bytes)
+}
+
 #[allow(unused_variables)]
 pub fn resolve_java(data_dir: &Path) -> std::io::Result<PathBuf> {
     #[cfg(feature = "embed-jre")]
@@ -81,7 +101,9 @@ pub fn resolve_java(data_dir: &Path) -> std::io::Result<PathBuf> {
     }
 }
 
-#[cfg(feature = "embed-jre")]
+/// Extracts `zip_bytes` into `target_dir`. Fails on any entry that would land outside
+/// `target_dir` (zip-slip) instead of silently skipping it.
+#[cfg(any(feature = "embed-jre", test))]
 pub fn extract_zip(zip_bytes: &[u8], target_dir: &Path) -> std::io::Result<()> {
     let reader = Cursor::new(zip_bytes);
     let mut archive = zip::ZipArchive::new(reader).map_err(io::Error::other)?;
@@ -90,8 +112,16 @@ pub fn extract_zip(zip_bytes: &[u8], target_dir: &Path) -> std::io::Result<()> {
         let mut file = archive.by_index(i).map_err(io::Error::other)?;
 
         let outpath = match file.enclosed_name() {
-            Some(path) => target_dir.join(path),
-            None => continue,
+            Some(path) if path_stays_within(&path) => target_dir.join(path),
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "Refusing to extract {}: path escapes target dir",
+                        file.name()
+                    ),
+                ));
+            }
         };
 
         if file.name().ends_with('/') {
@@ -108,3 +138,15 @@ pub fn extract_zip(zip_bytes: &[u8], target_dir: &Path) -> std::io::Result<()> {
     }
     Ok(())
 }
+
+/// True when `relative` has no root, prefix or `..` component, so joining it keeps it inside the
+/// base directory.
+#[cfg(any(feature = "embed-jre", test))]
+fn path_stays_within(relative: &Path) -> bool {
+    relative.components().all(|component| {
+        matches!(
+            component,
+            std::path::Component::Normal(_) | std::path::Component::CurDir
+        )
+    })
+}
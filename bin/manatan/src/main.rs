@@ -4,7 +4,7 @@ use std::{
     env,
     fs::{self},
     net::{Ipv4Addr, TcpListener},
-    path::{Path, PathBuf},
+    path::{Component, Path, PathBuf},
     process::Stdio,
     sync::{
         Arc, Mutex,
@@ -12,17 +12,20 @@ use std::{
         mpsc::{Receiver, Sender},
     },
     thread,
-    time::Duration,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use anyhow::anyhow;
 use axum::{
     Router,
+    extract::State,
     http::{StatusCode, Uri},
     response::IntoResponse,
-    routing::any,
+    routing::{any, get, post},
+};
+use clap::{
+    ArgMatches, CommandFactory, FromArgMatches, Parser, error::ErrorKind, parser::ValueSource,
 };
-use clap::Parser;
 use directories::{BaseDirs, ProjectDirs};
 use eframe::{
     egui::{self},
@@ -40,15 +43,19 @@ use reqwest::{
 };
 use rust_embed::RustEmbed;
 use self_update::update::ReleaseUpdate;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tokio::process::Command;
-use tower_http::cors::{AllowOrigin, CorsLayer};
-use tracing::{error, info, warn};
+use tower_http::{
+    LatencyUnit,
+    cors::{AllowOrigin, CorsLayer},
+    trace::{DefaultOnRequest, DefaultOnResponse, TraceLayer},
+};
+use tracing::{Level, error, info, warn};
 use tracing_subscriber::EnvFilter;
 
 #[cfg(feature = "embed-jre")]
 use crate::io::extract_zip;
-use crate::io::{extract_file, resolve_java};
+use crate::io::{ensure_extracted, resolve_java};
 
 const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
 const APP_NAME: &str = "Manatan";
@@ -59,9 +66,10 @@ const LEGACY_DATA_DIR_NAME: &str = "mangatan";
 const BIN_NAME: &str = "manatan";
 const SUWAYOMI_HOST: &str = "127.0.0.1";
 const SUWAYOMI_PORT: u16 = 4566;
-const SUWAYOMI_HTTP_BASE_URL: &str = "http://127.0.0.1:4566";
+const MANATAN_PID_FILE: &str = "manatan.pid";
 
 static ICON_BYTES: &[u8] = include_bytes!("../resources/faviconlogo.png");
+static OPENAPI_JSON: &str = include_str!("../resources/openapi.json");
 static JAR_BYTES: &[u8] = include_bytes!("../resources/Suwayomi-Server.jar");
 
 #[cfg(feature = "embed-jre")]
@@ -75,6 +83,21 @@ struct FrontendAssets;
 struct VersionResponse {
     version: String,
     variant: String,
+    update_status: String,
+}
+
+/// Build details for bug reports, served at `GET /api/system/info`.
+#[derive(Serialize)]
+struct SystemInfo {
+    version: &'static str,
+    git_commit: Option<&'static str>,
+    target: &'static str,
+    variant: &'static str,
+    features: Vec<&'static str>,
+}
+
+fn compiled_value(value: &'static str) -> Option<&'static str> {
+    Some(value).filter(|value| !value.is_empty())
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -88,9 +111,26 @@ enum UpdateStatus {
     Error(String),
 }
 
+type SharedUpdateStatus = Arc<Mutex<UpdateStatus>>;
+
+impl UpdateStatus {
+    /// Maps onto the `idle`/`downloading`/`ready` values the Android build reports.
+    fn api_value(&self) -> &'static str {
+        match self {
+            UpdateStatus::Downloading => "downloading",
+            UpdateStatus::RestartRequired => "ready",
+            _ => "idle",
+        }
+    }
+}
+
 #[derive(Parser, Debug, Clone)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
+    /// Loads settings from a TOML file. CLI flags and env vars take precedence over its values
+    #[arg(long, env = "MANATAN_CONFIG")]
+    config: Option<PathBuf>,
+
     /// Runs the server without the GUI (Fixes Docker/Server deployments)
     #[arg(long, env = "MANATAN_HEADLESS")]
     headless: bool,
@@ -99,6 +139,10 @@ struct Cli {
     #[arg(long, requires = "headless")]
     open_page: bool,
 
+    /// Delays opening the browser until the Suwayomi runtime is up, so sources are loaded
+    #[arg(long, env = "MANATAN_OPEN_WHEN_SOURCES_READY")]
+    open_when_sources_ready: bool,
+
     /// Sets the IP address to bind the server to
     #[arg(long, default_value = "0.0.0.0", env = "MANATAN_HOST")]
     host: Ipv4Addr,
@@ -130,6 +174,22 @@ struct Cli {
     #[arg(long, env = "MANATAN_JAVA_URL")]
     java_url: Option<String>,
 
+    /// Seconds to wait for the runtime bridge to come up before giving up
+    #[arg(long, env = "MANATAN_BRIDGE_TIMEOUT_SECS", default_value_t = 60)]
+    bridge_timeout_secs: u64,
+
+    /// Milliseconds between runtime bridge health probes while waiting for it to come up
+    #[arg(long, env = "MANATAN_BRIDGE_POLL_INTERVAL_MS", default_value_t = 1000)]
+    bridge_poll_interval_ms: u64,
+
+    /// Address the bundled Suwayomi runtime binds to
+    #[arg(long, default_value = SUWAYOMI_HOST, env = "MANATAN_SUWAYOMI_HOST")]
+    suwayomi_host: String,
+
+    /// Port the bundled Suwayomi runtime binds to
+    #[arg(long, default_value_t = SUWAYOMI_PORT, env = "MANATAN_SUWAYOMI_PORT")]
+    suwayomi_port: u16,
+
     /// Enable remote tracker search
     #[arg(
         long,
@@ -183,6 +243,180 @@ struct Cli {
     /// Local novel directory (absolute or relative to data dir)
     #[arg(long, env = "MANATAN_LOCAL_LN_PATH")]
     local_novel_path: Option<PathBuf>,
+
+    /// Allow relative path options to resolve outside the data dir (e.g. `../elsewhere`)
+    #[arg(long, env = "MANATAN_ALLOW_EXTERNAL_PATHS")]
+    allow_external_paths: bool,
+
+    /// Keep the server and Suwayomi running after the GUI window is closed
+    #[arg(long, env = "MANATAN_KEEP_SERVER_ON_CLOSE")]
+    keep_server_on_close: bool,
+
+    /// Log method, path, status and latency of every request at debug level
+    #[arg(long, env = "MANATAN_ACCESS_LOG")]
+    access_log: bool,
+
+    /// Expose control endpoints such as `POST /api/system/restart`
+    #[arg(long, env = "MANATAN_CONTROL_API")]
+    control_api: bool,
+
+    /// Re-check for updates every N hours while the app stays open (0 disables)
+    #[arg(long, env = "MANATAN_UPDATE_CHECK_INTERVAL_HOURS", default_value_t = 0)]
+    update_check_interval_hours: u64,
+
+    /// Skip the OCR subserver; `/api/ocr/*` answers 503
+    #[arg(long, env = "MANATAN_DISABLE_OCR")]
+    disable_ocr: bool,
+
+    /// Skip the Yomitan dictionary subserver; `/api/yomitan/*` answers 503
+    #[arg(long, env = "MANATAN_DISABLE_YOMITAN")]
+    disable_yomitan: bool,
+
+    /// Skip the audio subserver; `/api/audio/*` answers 503
+    #[arg(long, env = "MANATAN_DISABLE_AUDIO")]
+    disable_audio: bool,
+
+    /// Skip the sync subserver; `/api/sync/*` answers 503
+    #[arg(long, env = "MANATAN_DISABLE_SYNC")]
+    disable_sync: bool,
+
+    /// Skip the novel subserver; `/api/novel/*` answers 503
+    #[arg(long, env = "MANATAN_DISABLE_NOVEL")]
+    disable_novel: bool,
+
+    /// Serve the WebUI from this directory instead of the embedded build (frontend development)
+    #[arg(long, env = "MANATAN_WEBUI_DIR")]
+    webui_dir: Option<PathBuf>,
+}
+
+/// Settings accepted by `--config`. Keys match the long flag names with `_` instead of `-`.
+#[derive(Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    headless: Option<bool>,
+    open_page: Option<bool>,
+    open_when_sources_ready: Option<bool>,
+    host: Option<Ipv4Addr>,
+    port: Option<u16>,
+    db_path: Option<PathBuf>,
+    migrate_path: Option<PathBuf>,
+    runtime_only: Option<bool>,
+    java_url: Option<String>,
+    bridge_timeout_secs: Option<u64>,
+    bridge_poll_interval_ms: Option<u64>,
+    suwayomi_host: Option<String>,
+    suwayomi_port: Option<u16>,
+    tracker_remote_search: Option<bool>,
+    tracker_search_ttl_seconds: Option<i64>,
+    downloads_path: Option<PathBuf>,
+    aidoku_index_url: Option<String>,
+    aidoku_enabled: Option<bool>,
+    aidoku_cache_path: Option<PathBuf>,
+    local_manga_path: Option<PathBuf>,
+    local_anime_path: Option<PathBuf>,
+    local_novel_path: Option<PathBuf>,
+    allow_external_paths: Option<bool>,
+    keep_server_on_close: Option<bool>,
+    access_log: Option<bool>,
+    control_api: Option<bool>,
+    update_check_interval_hours: Option<u64>,
+    disable_ocr: Option<bool>,
+    disable_yomitan: Option<bool>,
+    disable_audio: Option<bool>,
+    disable_sync: Option<bool>,
+    disable_novel: Option<bool>,
+    webui_dir: Option<PathBuf>,
+}
+
+impl ConfigFile {
+    fn load(path: &Path) -> Result<Self, String> {
+        let raw = fs::read_to_string(path)
+            .map_err(|err| format!("Failed to read config {}: {err}", path.display()))?;
+        toml::from_str(&raw)
+            .map_err(|err| format!("Failed to parse config {}: {err}", path.display()))
+    }
+
+    /// Copies file values into `cli` for every setting not given on the command line or via env.
+    fn apply(self, cli: &mut Cli, matches: &ArgMatches) {
+        let explicit = |id: &str| {
+            matches!(
+                matches.value_source(id),
+                Some(ValueSource::CommandLine | ValueSource::EnvVariable)
+            )
+        };
+        macro_rules! fill {
+            ($($field:ident),* $(,)?) => {$(
+                if let Some(value) = self.$field
+                    && !explicit(stringify!($field))
+                {
+                    cli.$field = value;
+                }
+            )*};
+        }
+        macro_rules! fill_optional {
+            ($($field:ident),* $(,)?) => {$(
+                if let Some(value) = self.$field
+                    && !explicit(stringify!($field))
+                {
+                    cli.$field = Some(value);
+                }
+            )*};
+        }
+        fill!(
+            headless,
+            open_page,
+            open_when_sources_ready,
+            host,
+            port,
+            runtime_only,
+            bridge_timeout_secs,
+            bridge_poll_interval_ms,
+            suwayomi_host,
+            suwayomi_port,
+            tracker_remote_search,
+            tracker_search_ttl_seconds,
+            aidoku_enabled,
+            allow_external_paths,
+            keep_server_on_close,
+            access_log,
+            control_api,
+            update_check_interval_hours,
+            disable_ocr,
+            disable_yomitan,
+            disable_audio,
+            disable_sync,
+            disable_novel,
+        );
+        fill_optional!(
+            db_path,
+            migrate_path,
+            java_url,
+            downloads_path,
+            aidoku_index_url,
+            aidoku_cache_path,
+            local_manga_path,
+            local_anime_path,
+            local_novel_path,
+            webui_dir,
+        );
+    }
+}
+
+impl Cli {
+    /// Parses CLI args and env vars, then fills the remaining defaults from `--config`.
+    fn load() -> Self {
+        let matches = Cli::command().get_matches();
+        Self::from_matches(&matches)
+            .unwrap_or_else(|err| Cli::command().error(ErrorKind::InvalidValue, err).exit())
+    }
+
+    fn from_matches(matches: &ArgMatches) -> Result<Self, String> {
+        let mut cli = Cli::from_arg_matches(matches).map_err(|err| err.to_string())?;
+        if let Some(path) = cli.config.clone() {
+            ConfigFile::load(&path)?.apply(&mut cli, matches);
+        }
+        Ok(cli)
+    }
 }
 
 fn parse_boolish(value: &str) -> Result<bool, String> {
@@ -193,18 +427,57 @@ fn parse_boolish(value: &str) -> Result<bool, String> {
     }
 }
 
+/// Resolves a path option against the data dir. Relative paths that climb out of the data dir
+/// are replaced by the default unless `allow_external` is set.
 fn resolve_path_option(
     option: Option<&PathBuf>,
     data_dir: &Path,
     default_relative: &str,
+    allow_external: bool,
 ) -> String {
-    match option {
-        Some(path) if path.is_absolute() => path.clone(),
-        Some(path) => data_dir.join(path),
-        None => data_dir.join(default_relative),
+    let default_path = normalize_path(&data_dir.join(default_relative));
+    let resolved = match option {
+        Some(path) if path.is_absolute() => normalize_path(path),
+        Some(path) => {
+            let joined = normalize_path(&data_dir.join(path));
+            if joined.starts_with(normalize_path(data_dir)) {
+                joined
+            } else if allow_external {
+                warn!(
+                    "⚠️ Path option {} resolves outside the data dir: {}",
+                    path.display(),
+                    joined.display()
+                );
+                joined
+            } else {
+                warn!(
+                    "⚠️ Ignoring path option {} (resolves to {} outside the data dir); using {}. Pass --allow-external-paths to keep it.",
+                    path.display(),
+                    joined.display(),
+                    default_path.display()
+                );
+                default_path
+            }
+        }
+        None => default_path,
+    };
+    resolved.to_string_lossy().to_string()
+}
+
+/// Removes `.` and `..` components without touching the filesystem, so paths that don't exist
+/// yet still normalize.
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other.as_os_str()),
+        }
     }
-    .to_string_lossy()
-    .to_string()
+    out
 }
 
 fn resolve_data_dir() -> PathBuf {
@@ -600,7 +873,7 @@ fn main() -> eframe::Result<()> {
         return Ok(());
     }
 
-    let args = Cli::parse();
+    let args = Cli::load();
 
     let rust_log = env::var(EnvFilter::DEFAULT_ENV).unwrap_or_default();
     let env_filter = match rust_log.is_empty() {
@@ -616,6 +889,23 @@ fn main() -> eframe::Result<()> {
 
     let host = args.host;
     let port = args.port;
+    let update_status: SharedUpdateStatus = Arc::new(Mutex::new(UpdateStatus::Idle));
+
+    let manatan_pid_path = data_dir.join(MANATAN_PID_FILE);
+    if let Some(pid) = running_instance_pid(&manatan_pid_path, std::process::id()) {
+        error!("❌ Manatan is already running (pid {pid}) — open the existing window.");
+        if args.headless {
+            std::process::exit(1);
+        }
+        return show_already_running(host, port);
+    }
+    let _ = fs::create_dir_all(&data_dir);
+    if let Err(err) = fs::write(&manatan_pid_path, std::process::id().to_string()) {
+        warn!(
+            "Failed to write Manatan pid file {}: {err}",
+            manatan_pid_path.display()
+        );
+    }
 
     if args.headless {
         info!("👻 Starting in Headless Mode (No GUI)...");
@@ -624,7 +914,10 @@ fn main() -> eframe::Result<()> {
 
         rt.block_on(async {
             if args.open_page {
-                tokio::spawn(async move { open_webpage_when_ready(host, port).await });
+                let runtime_health = runtime_health_url_for_open(&args);
+                tokio::spawn(
+                    async move { open_webpage_when_ready(host, port, runtime_health).await },
+                );
             }
 
             let (shutdown_tx, shutdown_rx) = tokio::sync::mpsc::channel::<()>(1);
@@ -634,11 +927,21 @@ fn main() -> eframe::Result<()> {
                 let _ = shutdown_tx.send(()).await;
             });
 
-            if let Err(err) = run_server(shutdown_rx, &server_data_dir, host, port, &args).await {
+            if let Err(err) = run_server(
+                shutdown_rx,
+                &server_data_dir,
+                host,
+                port,
+                &args,
+                update_status,
+            )
+            .await
+            {
                 error!("Server crashed: {err}");
             }
         });
 
+        let _ = fs::remove_file(&manatan_pid_path);
         return Ok(());
     }
 
@@ -648,7 +951,8 @@ fn main() -> eframe::Result<()> {
 
     let thread_host = host;
     let thread_args = args.clone();
-    thread::spawn(move || {
+    let thread_update_status = update_status.clone();
+    let server_thread = thread::spawn(move || {
         let rt = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
         rt.block_on(async {
             let _guard = ServerGuard {
@@ -656,7 +960,8 @@ fn main() -> eframe::Result<()> {
             };
 
             let h = thread_host;
-            tokio::spawn(async move { open_webpage_when_ready(h, port).await });
+            let runtime_health = runtime_health_url_for_open(&thread_args);
+            tokio::spawn(async move { open_webpage_when_ready(h, port, runtime_health).await });
 
             if let Err(err) = run_server(
                 shutdown_rx,
@@ -664,6 +969,7 @@ fn main() -> eframe::Result<()> {
                 thread_host,
                 port,
                 &thread_args,
+                thread_update_status,
             )
             .await
             {
@@ -694,18 +1000,30 @@ fn main() -> eframe::Result<()> {
         ..Default::default()
     };
 
+    let close_action = CloseAction::from_flag(args.keep_server_on_close);
+
+    // Trigger a check immediately on startup, then optionally on an interval
+    spawn_update_checker(
+        update_status.clone(),
+        update_check_interval(args.update_check_interval_hours),
+    );
+
     info!("🎨 Attempting to open GUI window...");
     let result = eframe::run_native(
         APP_NAME,
         options,
         Box::new(move |_cc| {
             Ok(Box::new(MyApp::new(
-                shutdown_tx,
-                server_stopped_rx,
+                ServerShutdown {
+                    shutdown_tx,
+                    server_stopped_rx,
+                    shutdown_requested,
+                },
                 gui_data_dir,
-                shutdown_requested,
+                update_status,
                 host,
                 port,
+                close_action,
             )))
         }),
     );
@@ -715,11 +1033,61 @@ fn main() -> eframe::Result<()> {
         std::thread::sleep(std::time::Duration::from_secs(5));
     } else {
         info!("👋 GUI exited normally.");
+        if close_action == CloseAction::Detach {
+            info!("🔌 Server keeps running in the background. Send SIGTERM/Ctrl+C to stop it.");
+            let _ = server_thread.join();
+        }
     }
 
+    let _ = fs::remove_file(&manatan_pid_path);
     result
 }
 
+/// Small window shown instead of the launcher when another instance owns the data dir.
+fn show_already_running(host: Ipv4Addr, port: u16) -> eframe::Result<()> {
+    let icon = icon_data::from_png_bytes(ICON_BYTES).expect("The icon data must be valid");
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default()
+            .with_inner_size([320.0, 160.0])
+            .with_icon(icon)
+            .with_title(APP_NAME)
+            .with_resizable(false)
+            .with_maximize_button(false),
+        ..Default::default()
+    };
+    eframe::run_native(
+        APP_NAME,
+        options,
+        Box::new(move |_cc| Ok(Box::new(AlreadyRunningApp { host, port }))),
+    )
+}
+
+struct AlreadyRunningApp {
+    host: Ipv4Addr,
+    port: u16,
+}
+
+impl eframe::App for AlreadyRunningApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.add_space(20.0);
+                ui.heading("Manatan is already running");
+                ui.label("Open the existing window instead.");
+                ui.add_space(10.0);
+                if ui.button("🚀 Open Web UI").clicked() {
+                    let host_target = if self.host == Ipv4Addr::new(0, 0, 0, 0) {
+                        "localhost".to_string()
+                    } else {
+                        self.host.to_string()
+                    };
+                    let _ = open::that(format!("http://{host_target}:{}", self.port));
+                }
+            });
+        });
+    }
+}
+
 struct ServerGuard {
     tx: Sender<()>,
 }
@@ -729,36 +1097,58 @@ impl Drop for ServerGuard {
     }
 }
 
+/// What closing the GUI window does to the server.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CloseAction {
+    /// Stop the server and Suwayomi, then exit (default).
+    Terminate,
+    /// Close only the window; the server keeps running until a shutdown signal arrives.
+    Detach,
+}
+
+impl CloseAction {
+    fn from_flag(keep_server_on_close: bool) -> Self {
+        if keep_server_on_close {
+            CloseAction::Detach
+        } else {
+            CloseAction::Terminate
+        }
+    }
+}
+
+/// How the GUI asks the server thread to stop and learns that it has.
+struct ServerShutdown {
+    shutdown_tx: tokio::sync::mpsc::Sender<()>,
+    server_stopped_rx: Receiver<()>,
+    shutdown_requested: Arc<AtomicBool>,
+}
+
 struct MyApp {
     shutdown_tx: tokio::sync::mpsc::Sender<()>,
     server_stopped_rx: Receiver<()>,
     is_shutting_down: bool,
     data_dir: PathBuf,
-    update_status: Arc<Mutex<UpdateStatus>>,
+    update_status: SharedUpdateStatus,
     shutdown_requested: Arc<AtomicBool>,
     host: Ipv4Addr,
     port: u16,
+    close_action: CloseAction,
 }
 
 impl MyApp {
     fn new(
-        shutdown_tx: tokio::sync::mpsc::Sender<()>,
-        server_stopped_rx: Receiver<()>,
+        shutdown: ServerShutdown,
         data_dir: PathBuf,
-        shutdown_requested: Arc<AtomicBool>,
+        update_status: SharedUpdateStatus,
         host: Ipv4Addr,
         port: u16,
+        close_action: CloseAction,
     ) -> Self {
-        // Initialize status
-        let update_status = Arc::new(Mutex::new(UpdateStatus::Idle));
-
-        // Optional: Trigger a check immediately on startup
-        let status_clone = update_status.clone();
-        std::thread::spawn(move || {
-            if !is_flatpak() {
-                check_for_updates(status_clone);
-            }
-        });
+        let ServerShutdown {
+            shutdown_tx,
+            server_stopped_rx,
+            shutdown_requested,
+        } = shutdown;
 
         Self {
             shutdown_tx,
@@ -769,6 +1159,7 @@ impl MyApp {
             shutdown_requested,
             host,
             port,
+            close_action,
         }
     }
 
@@ -805,8 +1196,15 @@ impl eframe::App for MyApp {
 
         // Handle window close requests
         if ctx.input(|i| i.viewport().close_requested()) {
-            self.begin_shutdown("❌ Close requested.");
-            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+            match self.close_action {
+                CloseAction::Terminate => {
+                    self.begin_shutdown("❌ Close requested.");
+                    ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+                }
+                CloseAction::Detach => {
+                    info!("🪟 Close requested; leaving the server running.");
+                }
+            }
         }
 
         if self.is_shutting_down {
@@ -821,6 +1219,7 @@ impl eframe::App for MyApp {
             });
 
             if self.server_stopped_rx.try_recv().is_ok() {
+                let _ = fs::remove_file(self.data_dir.join(MANATAN_PID_FILE));
                 std::process::exit(0);
             }
             ctx.request_repaint();
@@ -906,14 +1305,7 @@ impl eframe::App for MyApp {
                             ui.colored_label(egui::Color32::GREEN, "✔ Update Ready!");
                             ui.add_space(5.0);
                             if ui.button("🚀 Restart App").clicked() {
-                                if let Ok(exe_path) = std::env::current_exe() {
-                                    let mut exe_str = exe_path.to_string_lossy().to_string();
-                                    if cfg!(target_os = "linux") && exe_str.ends_with(" (deleted)")
-                                    {
-                                        exe_str = exe_str.replace(" (deleted)", "");
-                                    }
-                                    let _ = std::process::Command::new(exe_str).spawn();
-                                }
+                                let _ = relaunch_current_exe(&self.data_dir.join(MANATAN_PID_FILE));
                                 ctx.send_viewport_cmd(egui::ViewportCommand::Close);
                             }
                         });
@@ -992,6 +1384,7 @@ async fn run_server(
     host: Ipv4Addr,
     port: u16,
     cli: &Cli,
+    update_status: SharedUpdateStatus,
 ) -> Result<(), Box<anyhow::Error>> {
     info!("🚀 Initializing Manatan Launcher...");
     info!("📂 Data Directory: {}", data_dir.display());
@@ -1035,10 +1428,7 @@ async fn run_server(
     migrate_suwayomi_database(data_dir);
     migrate_suwayomi_settings(data_dir);
 
-    info!("📦 Extracting assets...");
     let jar_name = "Suwayomi-Server.jar";
-    let _ = extract_file(&bin_dir, jar_name, JAR_BYTES)
-        .map_err(|err| anyhow!("Failed to extract {jar_name} {err:?}"))?;
     let jar_rel_path = PathBuf::from("bin").join(jar_name);
 
     #[cfg(feature = "embed-jre")]
@@ -1062,8 +1452,18 @@ async fn run_server(
         .and_then(|p| p.parent())
         .unwrap_or(data_dir);
 
+    info!("📦 Extracting assets...");
+    ensure_extracted(&bin_dir, jar_name, JAR_BYTES)
+        .map_err(|err| anyhow!("Failed to extract {jar_name} {err:?}"))?;
+
     info!("☕ Spawning Suwayomi...");
-    let manatan_db_path = resolve_path_option(cli.db_path.as_ref(), data_dir, "manatan.sqlite");
+    let allow_external = cli.allow_external_paths;
+    let manatan_db_path = resolve_path_option(
+        cli.db_path.as_ref(),
+        data_dir,
+        "manatan.sqlite",
+        allow_external,
+    );
     let manatan_migrate_path = cli
         .migrate_path
         .as_ref()
@@ -1075,7 +1475,9 @@ async fn run_server(
 
     let suwayomi_pid_path = data_dir.join("suwayomi.pid");
     cleanup_orphan_suwayomi(&suwayomi_pid_path);
-    ensure_suwayomi_port_available(SUWAYOMI_HOST, SUWAYOMI_PORT)?;
+    let suwayomi_host = cli.suwayomi_host.as_str();
+    let suwayomi_port = cli.suwayomi_port;
+    ensure_suwayomi_port_available(suwayomi_host, suwayomi_port)?;
 
     let mut suwayomi_proc = Command::new(&java_exec)
         .current_dir(data_dir)
@@ -1084,23 +1486,12 @@ async fn run_server(
             "SUWAYOMI_RUNTIME_ONLY",
             if runtime_only { "true" } else { "false" },
         )
-        .arg("-Dsuwayomi.tachidesk.config.server.initialOpenInBrowserEnabled=false")
-        .arg("-Dsuwayomi.tachidesk.config.server.webUIEnabled=false")
-        .arg("-Dsuwayomi.tachidesk.config.server.enableCookieApi=true")
-        .arg(format!("-Dsuwayomi.runtimeOnly={runtime_only}"))
-        .arg(format!(
-            "-Dsuwayomi.tachidesk.config.server.rootDir={}",
-            data_dir.display()
-        ))
-        .arg(format!(
-            "-Dsuwayomi.tachidesk.config.server.ip={SUWAYOMI_HOST}"
-        ))
-        .arg(format!(
-            "-Dsuwayomi.tachidesk.config.server.port={SUWAYOMI_PORT}"
-        ))
-        .arg(format!(
-            "-Dsuwayomi.tachidesk.config.server.localAnimeSourcePath={}",
-            local_anime_dir.display()
+        .args(suwayomi_config_args(
+            data_dir,
+            &local_anime_dir,
+            suwayomi_host,
+            suwayomi_port,
+            runtime_only,
         ))
         .arg("-XX:+ExitOnOutOfMemoryError")
         .arg("--enable-native-access=ALL-UNNAMED")
@@ -1128,33 +1519,56 @@ async fn run_server(
         );
     }
 
-    let manatan_runtime_url = if runtime_only {
-        if let Some(value) = cli.java_url.as_deref()
-            && value != SUWAYOMI_HTTP_BASE_URL
-        {
-            warn!(
-                "Ignoring MANATAN_JAVA_URL={} while runtime-only is enabled; using {}",
-                value, SUWAYOMI_HTTP_BASE_URL
-            );
-        }
-        SUWAYOMI_HTTP_BASE_URL.to_string()
-    } else {
-        cli.java_url
-            .clone()
-            .unwrap_or_else(|| SUWAYOMI_HTTP_BASE_URL.to_string())
-    };
+    let suwayomi_base_url = suwayomi_http_base_url(suwayomi_host, suwayomi_port);
+    if runtime_only
+        && let Some(value) = cli.java_url.as_deref()
+        && value != suwayomi_base_url
+    {
+        warn!(
+            "Ignoring MANATAN_JAVA_URL={} while runtime-only is enabled; using {}",
+            value, suwayomi_base_url
+        );
+    }
+    let manatan_runtime_url = runtime_base_url(cli);
     let tracker_remote_search = cli.tracker_remote_search;
     let tracker_search_ttl_seconds = cli.tracker_search_ttl_seconds;
-    let downloads_path = resolve_path_option(cli.downloads_path.as_ref(), data_dir, "downloads");
+    let downloads_path = resolve_path_option(
+        cli.downloads_path.as_ref(),
+        data_dir,
+        "downloads",
+        allow_external,
+    );
     let aidoku_index_url = cli.aidoku_index_url.clone().unwrap_or_default();
     let aidoku_enabled = cli.aidoku_enabled;
-    let aidoku_cache_path = resolve_path_option(cli.aidoku_cache_path.as_ref(), data_dir, "aidoku");
-    let local_manga_path =
-        resolve_path_option(cli.local_manga_path.as_ref(), data_dir, "local-manga");
-    let local_anime_path =
-        resolve_path_option(cli.local_anime_path.as_ref(), data_dir, "local-anime");
-    let local_novel_path_str =
-        resolve_path_option(cli.local_novel_path.as_ref(), data_dir, "local-novel");
+    let aidoku_cache_path = resolve_path_option(
+        cli.aidoku_cache_path.as_ref(),
+        data_dir,
+        "aidoku",
+        allow_external,
+    );
+    let local_manga_path = resolve_path_option(
+        cli.local_manga_path.as_ref(),
+        data_dir,
+        "local-manga",
+        allow_external,
+    );
+    let local_anime_path = resolve_path_option(
+        cli.local_anime_path.as_ref(),
+        data_dir,
+        "local-anime",
+        allow_external,
+    );
+    let local_novel_path_str = resolve_path_option(
+        cli.local_novel_path.as_ref(),
+        data_dir,
+        "local-novel",
+        allow_external,
+    );
+    let onboarding_dirs = vec![
+        PathBuf::from(&local_manga_path),
+        PathBuf::from(&local_anime_path),
+        PathBuf::from(&local_novel_path_str),
+    ];
     let manatan_config = ManatanServerConfig {
         host: host.to_string(),
         port,
@@ -1174,19 +1588,61 @@ async fn run_server(
     let manatan_state = build_state(manatan_config)
         .await
         .map_err(|err| anyhow!("Failed to init Manatan server: {err}"))?;
-    ensure_runtime_bridge_available(&manatan_runtime_url)
-        .await
-        .map_err(|err| anyhow!("Failed runtime bridge preflight: {err}"))?;
+    ensure_runtime_bridge_available(
+        &manatan_runtime_url,
+        Duration::from_secs(cli.bridge_timeout_secs),
+        Duration::from_millis(cli.bridge_poll_interval_ms),
+    )
+    .await
+    .map_err(|err| anyhow!("Failed runtime bridge preflight: {err}"))?;
     let manatan_router = build_router_without_cors(manatan_state);
 
     info!("🌍 Starting Web Interface at http://{}:{}", host, port);
 
-    let ocr_router = manatan_ocr_server::create_router(data_dir.clone());
-    let yomitan_router = manatan_yomitan_server::create_router(data_dir.clone());
-    let audio_router = manatan_audio_server::create_router(data_dir.clone());
-    let sync_router = manatan_sync_server::create_router(data_dir.clone());
-    let novel_router = manatan_novel_server::create_router(data_dir.clone(), PathBuf::from(local_novel_path_str));
-    let system_router = Router::new().route("/version", any(current_version_handler));
+    let ocr_router = subsystem_router("ocr", !cli.disable_ocr, || {
+        manatan_ocr_server::create_router(data_dir.clone())
+    });
+    let yomitan_router = subsystem_router("yomitan", !cli.disable_yomitan, || {
+        manatan_yomitan_server::create_router(data_dir.clone())
+    });
+    let audio_router = subsystem_router("audio", !cli.disable_audio, || {
+        manatan_audio_server::create_router(data_dir.clone())
+    });
+    let sync_router = subsystem_router("sync", !cli.disable_sync, || {
+        manatan_sync_server::create_router(data_dir.clone())
+    });
+    let novel_router = subsystem_router("novel", !cli.disable_novel, || {
+        manatan_novel_server::create_router(data_dir.clone(), PathBuf::from(local_novel_path_str))
+    });
+    let onboarding_data_dir = data_dir.clone();
+    let subsystems = vec![
+        ("ocr", !cli.disable_ocr),
+        ("yomitan", !cli.disable_yomitan),
+        ("audio", !cli.disable_audio),
+        ("sync", !cli.disable_sync),
+        ("novel", !cli.disable_novel),
+    ];
+    let probe_base_url = local_probe_base_url(host, port);
+    let mut system_router = Router::new()
+        .route("/version", any(current_version_handler))
+        .route("/info", get(system_info_handler))
+        .route(
+            "/onboarding",
+            get(move || onboarding_handler(onboarding_data_dir.clone(), onboarding_dirs.clone())),
+        )
+        .route(
+            "/subsystems",
+            get(move || subsystems_handler(probe_base_url.clone(), subsystems.clone())),
+        );
+    let restart = Arc::new(RestartRequest::default());
+    if cli.control_api {
+        let restart = Arc::clone(&restart);
+        system_router = system_router.route(
+            "/restart",
+            post(move || restart_app_handler(Arc::clone(&restart))),
+        );
+    }
+    let system_router = system_router.with_state(update_status);
 
     let cors = CorsLayer::new()
         .allow_origin(AllowOrigin::mirror_request())
@@ -1208,6 +1664,11 @@ async fn run_server(
         ])
         .allow_credentials(true);
 
+    let webui_dir = cli.webui_dir.clone();
+    if let Some(dir) = &webui_dir {
+        info!("🧪 Serving WebUI from {}", dir.display());
+    }
+
     let app = Router::new()
         .nest("/api/ocr", ocr_router)
         .nest("/api/audio", audio_router)
@@ -1215,18 +1676,25 @@ async fn run_server(
         .nest("/api/novel", novel_router)
         .nest("/api/system", system_router)
         .nest("/api/yomitan", yomitan_router)
+        .route("/api/openapi.json", any(openapi_handler))
         .merge(manatan_router)
-        .fallback(serve_react_app)
+        .fallback(move |uri: Uri| serve_react_app(uri, webui_dir.clone()))
         .layer(cors);
+    let app = with_access_log(app, cli.access_log);
 
     let listener_addr = format!("{host}:{port}");
     let listener = tokio::net::TcpListener::bind(&listener_addr)
         .await
         .map_err(|err| anyhow!("Failed to create main server socket: {err:?}"))?;
 
+    let restart_signal = Arc::clone(&restart);
     let server_future = axum::serve(listener, app).with_graceful_shutdown(async move {
-        let _ = shutdown_signal.recv().await;
-        info!("🛑 Shutdown signal received.");
+        tokio::select! {
+            _ = shutdown_signal.recv() => info!("🛑 Shutdown signal received."),
+            () = restart_signal.notify.notified() => {
+                info!("🔁 Restart requested; stopping server...");
+            }
+        }
     });
 
     info!("✅ Unified Server Running.");
@@ -1245,25 +1713,104 @@ async fn run_server(
     let _ = fs::remove_file(&suwayomi_pid_path);
     info!("   Suwayomi terminated.");
 
+    if restart.requested.load(Ordering::SeqCst) {
+        info!("🔁 Relaunching Manatan...");
+        match relaunch_current_exe(&data_dir.join(MANATAN_PID_FILE)) {
+            Ok(()) => std::process::exit(0),
+            Err(err) => error!("❌ Failed to relaunch Manatan: {err}"),
+        }
+    }
+
     Ok(())
 }
 
-async fn serve_react_app(uri: Uri) -> impl IntoResponse {
-    let path = uri.path().trim_start_matches('/');
+async fn serve_react_app(uri: Uri, webui_dir: Option<PathBuf>) -> impl IntoResponse {
+    react_app_response(uri.path(), &spa_excluded_prefixes(), |path| {
+        webui_asset(webui_dir.as_deref(), path, |path| {
+            FrontendAssets::get(path).map(|file| file.data)
+        })
+    })
+}
 
-    if !path.is_empty()
-        && let Some(content) = FrontendAssets::get(path)
-    {
-        let mime = mime_guess::from_path(path).first_or_octet_stream();
+/// Reads `path` from `webui_dir` when one is configured, otherwise (or when the file is missing
+/// there) from `embedded`. Paths that would escape the directory are never read from disk.
+fn webui_asset(
+    webui_dir: Option<&Path>,
+    path: &str,
+    embedded: impl Fn(&str) -> Option<std::borrow::Cow<'static, [u8]>>,
+) -> Option<std::borrow::Cow<'static, [u8]>> {
+    let relative = Path::new(path);
+    let from_dir = webui_dir
+        .filter(|_| {
+            relative
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+        })
+        .and_then(|dir| fs::read(dir.join(relative)).ok());
+    match from_dir {
+        Some(bytes) => Some(bytes.into()),
+        None => embedded(path),
+    }
+}
+
+#[derive(Serialize)]
+struct ApiErrorResponse {
+    error: &'static str,
+}
+
+/// Path prefixes that never fall back to the SPA, so a miss under them is a real 404.
+const DEFAULT_SPA_EXCLUDED_PREFIXES: [&str; 2] = ["/api", "/health"];
+
+/// [`DEFAULT_SPA_EXCLUDED_PREFIXES`] plus any from `MANATAN_SPA_EXCLUDED_PREFIXES` (comma
+/// separated).
+fn spa_excluded_prefixes() -> Vec<String> {
+    let extra = env::var("MANATAN_SPA_EXCLUDED_PREFIXES").unwrap_or_default();
+    DEFAULT_SPA_EXCLUDED_PREFIXES
+        .iter()
+        .map(|prefix| prefix.to_string())
+        .chain(
+            extra
+                .split(',')
+                .map(|prefix| format!("/{}", prefix.trim().trim_matches('/')))
+                .filter(|prefix| prefix.len() > 1),
+        )
+        .collect()
+}
+
+fn is_spa_excluded(path: &str, prefixes: &[String]) -> bool {
+    prefixes.iter().any(|prefix| {
+        path.strip_prefix(prefix.as_str())
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    })
+}
+
+/// Serves `path` from the WebUI assets `get_asset` resolves, falling back to `index.html` so
+/// client-side routes like `/library` load the app. Unmatched paths under `excluded_prefixes`
+/// get a JSON 404 instead.
+fn react_app_response(
+    path: &str,
+    excluded_prefixes: &[String],
+    get_asset: impl Fn(&str) -> Option<std::borrow::Cow<'static, [u8]>>,
+) -> axum::response::Response {
+    if is_spa_excluded(path, excluded_prefixes) {
         return (
-            [(axum::http::header::CONTENT_TYPE, mime.as_ref())],
-            content.data,
+            StatusCode::NOT_FOUND,
+            axum::Json(ApiErrorResponse { error: "not_found" }),
         )
             .into_response();
     }
 
-    if let Some(index) = FrontendAssets::get("index.html")
-        && let Ok(html_string) = std::str::from_utf8(index.data.as_ref())
+    let path = path.trim_start_matches('/');
+
+    if !path.is_empty()
+        && let Some(content) = get_asset(path)
+    {
+        let mime = mime_guess::from_path(path).first_or_octet_stream();
+        return ([(axum::http::header::CONTENT_TYPE, mime.as_ref())], content).into_response();
+    }
+
+    if let Some(index) = get_asset("index.html")
+        && let Ok(html_string) = std::str::from_utf8(index.as_ref())
     {
         let fixed_html = html_string.replace("<head>", "<head><base href=\"/\" />");
 
@@ -1277,6 +1824,52 @@ async fn serve_react_app(uri: Uri) -> impl IntoResponse {
     (StatusCode::NOT_FOUND, "404 - Index.html missing").into_response()
 }
 
+fn suwayomi_http_base_url(host: &str, port: u16) -> String {
+    format!("http://{host}:{port}")
+}
+
+/// JVM system properties that configure the spawned Suwayomi runtime.
+fn suwayomi_config_args(
+    data_dir: &Path,
+    local_anime_dir: &Path,
+    host: &str,
+    port: u16,
+    runtime_only: bool,
+) -> Vec<String> {
+    vec![
+        "-Dsuwayomi.tachidesk.config.server.initialOpenInBrowserEnabled=false".to_string(),
+        "-Dsuwayomi.tachidesk.config.server.webUIEnabled=false".to_string(),
+        "-Dsuwayomi.tachidesk.config.server.enableCookieApi=true".to_string(),
+        format!("-Dsuwayomi.runtimeOnly={runtime_only}"),
+        format!(
+            "-Dsuwayomi.tachidesk.config.server.rootDir={}",
+            data_dir.display()
+        ),
+        format!("-Dsuwayomi.tachidesk.config.server.ip={host}"),
+        format!("-Dsuwayomi.tachidesk.config.server.port={port}"),
+        format!(
+            "-Dsuwayomi.tachidesk.config.server.localAnimeSourcePath={}",
+            local_anime_dir.display()
+        ),
+    ]
+}
+
+/// Base URL of the Suwayomi runtime the server talks to. Runtime-only mode always uses the
+/// bundled runtime and ignores `--java-url`.
+fn runtime_base_url(cli: &Cli) -> String {
+    let suwayomi_base_url = suwayomi_http_base_url(&cli.suwayomi_host, cli.suwayomi_port);
+    if cli.runtime_only {
+        suwayomi_base_url
+    } else {
+        cli.java_url.clone().unwrap_or(suwayomi_base_url)
+    }
+}
+
+fn runtime_health_url_for_open(cli: &Cli) -> Option<String> {
+    cli.open_when_sources_ready
+        .then(|| format!("{}/runtime/v1/health", runtime_base_url(cli)))
+}
+
 fn ensure_suwayomi_port_available(host: &str, port: u16) -> anyhow::Result<()> {
     match TcpListener::bind((host, port)) {
         Ok(listener) => {
@@ -1289,21 +1882,40 @@ fn ensure_suwayomi_port_available(host: &str, port: u16) -> anyhow::Result<()> {
     }
 }
 
-async fn ensure_runtime_bridge_available(base_url: &str) -> anyhow::Result<()> {
+const MIN_BRIDGE_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Polls the runtime until its health endpoint answers, then checks the bridge route exists. Every
+/// request is bounded by the time left until `timeout`, so a runtime that accepts connections but
+/// never responds can't stall startup past it.
+async fn ensure_runtime_bridge_available(
+    base_url: &str,
+    timeout: Duration,
+    poll_interval: Duration,
+) -> anyhow::Result<()> {
     let client = Client::new();
     let health_url = format!("{base_url}/runtime/v1/health");
     let bridge_url = format!("{base_url}/runtime/v1/bridge/manga/pages");
+    let deadline = tokio::time::Instant::now() + timeout;
+    let poll_interval = poll_interval.max(MIN_BRIDGE_POLL_INTERVAL);
 
-    for _ in 0..60 {
-        if let Ok(resp) = client.get(&health_url).send().await
+    loop {
+        let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+        if let Ok(Ok(resp)) = tokio::time::timeout(remaining, client.get(&health_url).send()).await
             && resp.status().is_success()
         {
-            let bridge_resp = client
-                .post(&bridge_url)
-                .header("content-type", "application/json")
-                .body("{}")
-                .send()
-                .await;
+            let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+            let bridge_resp = tokio::time::timeout(
+                remaining,
+                client
+                    .post(&bridge_url)
+                    .header("content-type", "application/json")
+                    .body("{}")
+                    .send(),
+            )
+            .await
+            .map_err(|_| {
+                anyhow!("runtime bridge endpoint {bridge_url} did not respond within {timeout:?}")
+            })?;
 
             return match bridge_resp {
                 Ok(resp) if resp.status() == StatusCode::NOT_FOUND => {
@@ -1320,11 +1932,15 @@ async fn ensure_runtime_bridge_available(base_url: &str) -> anyhow::Result<()> {
             };
         }
 
-        tokio::time::sleep(Duration::from_secs(1)).await;
+        let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        tokio::time::sleep(remaining.min(poll_interval)).await;
     }
 
     Err(anyhow!(
-        "timed out waiting for runtime health endpoint {health_url}"
+        "timed out after {timeout:?} waiting for runtime health endpoint {health_url}"
     ))
 }
 
@@ -1357,30 +1973,55 @@ fn cleanup_orphan_suwayomi(pid_path: &Path) {
 
     #[cfg(unix)]
     {
-        if !is_suwayomi_process(pid) {
+        match pid_status(pid, "Suwayomi-Server.jar") {
+            PidStatus::Dead => {
+                let _ = fs::remove_file(pid_path);
+            }
+            PidStatus::Reused => {
+                warn!(
+                    "Stale pid file {} does not match Suwayomi process; removing.",
+                    pid_path.display()
+                );
+                let _ = fs::remove_file(pid_path);
+            }
+            PidStatus::Running if is_suwayomi_process(pid) => {
+                info!("Found leftover Suwayomi process (pid {pid}). Shutting it down...");
+                terminate_process(pid, Duration::from_secs(5));
+                let _ = fs::remove_file(pid_path);
+            }
+            PidStatus::Running => {
+                warn!(
+                    "Cannot inspect process {pid} from {}; leaving it running.",
+                    pid_path.display()
+                );
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        if pid_status(pid, "java") == PidStatus::Running {
             warn!(
-                "Stale pid file {} does not match Suwayomi process; removing.",
-                pid_path.display()
+                "Leftover Suwayomi process (pid {pid}) may still be running; stop it manually if startup fails."
             );
+        } else {
             let _ = fs::remove_file(pid_path);
-            return;
         }
+    }
+}
 
-        if !is_process_alive(pid) {
-            let _ = fs::remove_file(pid_path);
-            return;
-        }
+/// Returns the pid of another live Manatan instance recorded in `pid_path`. The pid file is only
+/// removed once its process is confirmed gone (or is ourselves).
+fn running_instance_pid(pid_path: &Path, own_pid: u32) -> Option<i32> {
+    let pid = read_pid_file(pid_path)?;
 
-        info!("Found leftover Suwayomi process (pid {pid}). Shutting it down...");
-        terminate_process(pid, Duration::from_secs(5));
-        let _ = fs::remove_file(pid_path);
+    if u32::try_from(pid).ok() != Some(own_pid) && pid_status(pid, BIN_NAME) == PidStatus::Running {
+        return Some(pid);
     }
 
-    #[cfg(not(unix))]
-    {
-        let _ = pid;
-        let _ = fs::remove_file(pid_path);
-    }
+    info!("Removing stale pid file {}", pid_path.display());
+    let _ = fs::remove_file(pid_path);
+    None
 }
 
 fn read_pid_file(pid_path: &Path) -> Option<i32> {
@@ -1399,6 +2040,86 @@ fn read_pid_file(pid_path: &Path) -> Option<i32> {
     }
 }
 
+/// What became of the process recorded in a pid file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PidStatus {
+    /// No process has this pid any more.
+    Dead,
+    /// A live process running something else: the pid was reused.
+    Reused,
+    /// A live process that matches, or one that can't be inspected.
+    Running,
+}
+
+/// Checks whether `pid` is alive and its command line mentions `name`. Only `Dead` and `Reused`
+/// are definitive; a process that can't be inspected counts as `Running`.
+#[cfg(unix)]
+fn pid_status(pid: i32, name: &str) -> PidStatus {
+    if !is_process_alive(pid) {
+        return PidStatus::Dead;
+    }
+    match process_command_line(pid) {
+        Some(command) if !command.contains(name) => PidStatus::Reused,
+        _ => PidStatus::Running,
+    }
+}
+
+/// Checks whether `pid` is alive and its executable path mentions `name`. Only `Dead` and
+/// `Reused` are definitive; a process that can't be inspected counts as `Running`.
+#[cfg(windows)]
+fn pid_status(pid: i32, name: &str) -> PidStatus {
+    use windows_sys::Win32::{
+        Foundation::{CloseHandle, ERROR_INVALID_PARAMETER, GetLastError, STILL_ACTIVE},
+        System::Threading::{
+            GetExitCodeProcess, OpenProcess, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+            QueryFullProcessImageNameW,
+        },
+    };
+
+    let Ok(pid) = u32::try_from(pid) else {
+        return PidStatus::Dead;
+    };
+    let handle = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid) };
+    if handle.is_null() {
+        // Unknown pids are rejected as an invalid parameter; any other failure (e.g. access
+        // denied) means the process exists.
+        return if unsafe { GetLastError() } == ERROR_INVALID_PARAMETER {
+            PidStatus::Dead
+        } else {
+            PidStatus::Running
+        };
+    }
+
+    let mut exit_code = 0u32;
+    let exited = unsafe { GetExitCodeProcess(handle, &mut exit_code) } != 0
+        && exit_code.cast_signed() != STILL_ACTIVE;
+    let mut image = [0u16; 1024];
+    let mut image_len = image.len() as u32;
+    let image_path = (unsafe {
+        QueryFullProcessImageNameW(
+            handle,
+            PROCESS_NAME_WIN32,
+            image.as_mut_ptr(),
+            &mut image_len,
+        )
+    } != 0)
+        .then(|| String::from_utf16_lossy(&image[..image_len as usize]));
+    unsafe { CloseHandle(handle) };
+
+    if exited {
+        return PidStatus::Dead;
+    }
+    match image_path {
+        Some(path) if !path.to_ascii_lowercase().contains(name) => PidStatus::Reused,
+        _ => PidStatus::Running,
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn pid_status(_pid: i32, _name: &str) -> PidStatus {
+    PidStatus::Running
+}
+
 #[cfg(unix)]
 fn is_process_alive(pid: i32) -> bool {
     let result = unsafe { libc::kill(pid, 0) };
@@ -1409,14 +2130,23 @@ fn is_process_alive(pid: i32) -> bool {
     err.raw_os_error() == Some(libc::EPERM)
 }
 
+/// The command line of `pid`, from /proc where it exists and `ps` elsewhere (macOS, the BSDs).
+#[cfg(unix)]
+fn process_command_line(pid: i32) -> Option<String> {
+    if let Ok(bytes) = fs::read(format!("/proc/{pid}/cmdline")) {
+        return Some(String::from_utf8_lossy(&bytes).replace('\0', " "));
+    }
+    let output = std::process::Command::new("ps")
+        .args(["-p", &pid.to_string(), "-o", "command="])
+        .output()
+        .ok()?;
+    let command = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !command.is_empty()).then_some(command)
+}
+
 #[cfg(unix)]
 fn is_suwayomi_process(pid: i32) -> bool {
-    let cmdline_path = format!("/proc/{pid}/cmdline");
-    let Ok(bytes) = fs::read(cmdline_path) else {
-        return false;
-    };
-    let text = String::from_utf8_lossy(&bytes).replace('\0', " ");
-    text.contains("Suwayomi-Server.jar")
+    process_command_line(pid).is_some_and(|command| command.contains("Suwayomi-Server.jar"))
 }
 
 #[cfg(unix)]
@@ -1432,6 +2162,45 @@ fn terminate_process(pid: i32, timeout: Duration) {
     let _ = unsafe { libc::kill(pid, libc::SIGKILL) };
 }
 
+fn update_check_interval(hours: u64) -> Option<Duration> {
+    (hours > 0).then(|| Duration::from_secs(hours.saturating_mul(3600)))
+}
+
+/// Delay until the next periodic update check: the interval plus up to 10% jitter, so
+/// instances started together don't all query GitHub at the same moment.
+fn next_update_check_delay(interval: Duration, seed: u64) -> Duration {
+    let max_jitter = interval.as_secs() / 10;
+    interval + Duration::from_secs(seed % (max_jitter + 1))
+}
+
+fn spawn_update_checker(status: SharedUpdateStatus, interval: Option<Duration>) {
+    if is_flatpak() {
+        return;
+    }
+    std::thread::spawn(move || {
+        check_for_updates(status.clone());
+        let Some(interval) = interval else {
+            return;
+        };
+        loop {
+            let seed = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| u64::from(d.subsec_nanos()))
+                .unwrap_or_default();
+            thread::sleep(next_update_check_delay(interval, seed));
+
+            // Leave in-flight or finished updates alone
+            let busy = matches!(
+                *status.lock().expect("lock shouldn't panic"),
+                UpdateStatus::Checking | UpdateStatus::Downloading | UpdateStatus::RestartRequired
+            );
+            if !busy {
+                check_for_updates(status.clone());
+            }
+        }
+    });
+}
+
 fn check_for_updates(status: Arc<Mutex<UpdateStatus>>) {
     *status.lock().expect("lock shouldn't panic") = UpdateStatus::Checking;
 
@@ -1556,7 +2325,17 @@ fn build_updater_with_download(
         .build()
 }
 
-async fn open_webpage_when_ready(host: Ipv4Addr, port: u16) {
+const WEB_READY_TIMEOUT: Duration = Duration::from_secs(10);
+const SOURCES_READY_TIMEOUT: Duration = Duration::from_secs(180);
+
+/// Whether the browser can be opened. `runtime_up` is `None` when we don't wait for the runtime.
+fn browser_ready(web_up: bool, runtime_up: Option<bool>) -> bool {
+    web_up && runtime_up.unwrap_or(true)
+}
+
+/// Opens the web UI once `/health` answers. With `runtime_health_url` set, also waits for the
+/// Suwayomi runtime so the library isn't empty on first open.
+async fn open_webpage_when_ready(host: Ipv4Addr, port: u16, runtime_health_url: Option<String>) {
     let client = Client::new();
 
     let host_target = if host == Ipv4Addr::new(0, 0, 0, 0) {
@@ -1566,33 +2345,52 @@ async fn open_webpage_when_ready(host: Ipv4Addr, port: u16) {
     };
     let url = format!("http://{host_target}:{port}");
     let health_url = format!("http://{host_target}:{port}/health");
+    let timeout = if runtime_health_url.is_some() {
+        SOURCES_READY_TIMEOUT
+    } else {
+        WEB_READY_TIMEOUT
+    };
 
-    info!("⏳ Polling health endpoint for readiness (timeout 10s)...");
+    info!(
+        "⏳ Polling health endpoint for readiness (timeout {}s)...",
+        timeout.as_secs()
+    );
 
     // Define the polling task
     let polling_task = async {
         loop {
-            match client.get(&health_url).send().await {
-                Ok(resp) if resp.status().is_success() => {
-                    info!("✅ Server is responsive! Opening browser...");
-                    if let Err(e) = open::that(&url) {
-                        error!("❌ Failed to open browser: {}", e);
-                    }
-                    return;
-                }
+            let web_up = match client.get(&health_url).send().await {
+                Ok(resp) if resp.status().is_success() => true,
                 err => {
                     warn!("Failed to poll health to open webpage: {err:?}");
-                    tokio::time::sleep(Duration::from_millis(500)).await;
+                    false
                 }
+            };
+            let runtime_up = match &runtime_health_url {
+                Some(runtime_url) if web_up => Some(matches!(
+                    client.get(runtime_url).send().await,
+                    Ok(resp) if resp.status().is_success()
+                )),
+                Some(_) => Some(false),
+                None => None,
+            };
+
+            if browser_ready(web_up, runtime_up) {
+                info!("✅ Server is responsive! Opening browser...");
+                if let Err(e) = open::that(&url) {
+                    error!("❌ Failed to open browser: {}", e);
+                }
+                return;
             }
+            tokio::time::sleep(Duration::from_millis(500)).await;
         }
     };
 
-    if tokio::time::timeout(Duration::from_secs(10), polling_task)
-        .await
-        .is_err()
-    {
-        error!("❌ Timed out waiting for server readiness (10s). Browser open cancelled.");
+    if tokio::time::timeout(timeout, polling_task).await.is_err() {
+        error!(
+            "❌ Timed out waiting for server readiness ({}s). Browser open cancelled.",
+            timeout.as_secs()
+        );
     }
 }
 
@@ -1620,13 +2418,813 @@ async fn wait_for_shutdown_signal() {
     }
 }
 
-async fn current_version_handler() -> impl IntoResponse {
+async fn current_version_handler(
+    State(update_status): State<SharedUpdateStatus>,
+) -> impl IntoResponse {
+    let update_status = update_status
+        .lock()
+        .expect("lock shouldn't panic")
+        .api_value()
+        .to_string();
     axum::Json(VersionResponse {
         version: APP_VERSION.to_string(),
         variant: "desktop".to_string(), // Frontend will see 'desktop' and HIDE the button
+        update_status,
+    })
+}
+
+async fn system_info_handler() -> impl IntoResponse {
+    axum::Json(SystemInfo {
+        version: APP_VERSION,
+        git_commit: compiled_value(env!("MANATAN_GIT_COMMIT_COMPILED")),
+        target: env!("MANATAN_BUILD_TARGET_COMPILED"),
+        variant: "desktop",
+        features: [("embed-jre", cfg!(feature = "embed-jre"))]
+            .into_iter()
+            .filter_map(|(name, enabled)| enabled.then_some(name))
+            .collect(),
     })
 }
 
+/// First-run checklist for the frontend. `complete` is set once every step is done.
+#[derive(Serialize, Debug)]
+struct OnboardingStatus {
+    storage_permission: bool,
+    dictionary_installed: bool,
+    source_configured: bool,
+    local_dirs_present: bool,
+    complete: bool,
+}
+
+/// Suwayomi keeps installed extensions as jars under `<rootDir>/extensions`.
+fn has_installed_extensions(suwayomi_root: &Path) -> bool {
+    fs::read_dir(suwayomi_root.join("extensions"))
+        .map(|entries| {
+            entries
+                .flatten()
+                .any(|entry| entry.path().extension().is_some_and(|ext| ext == "jar"))
+        })
+        .unwrap_or(false)
+}
+
+fn onboarding_status(data_dir: &Path, local_dirs: &[PathBuf]) -> OnboardingStatus {
+    // Desktop storage needs no runtime grant.
+    let storage_permission = true;
+    let dictionary_installed = manatan_yomitan_server::installed_dictionary_count(data_dir) > 0;
+    let source_configured = has_installed_extensions(data_dir);
+    let local_dirs_present = local_dirs.iter().all(|dir| dir.is_dir());
+    OnboardingStatus {
+        storage_permission,
+        dictionary_installed,
+        source_configured,
+        local_dirs_present,
+        complete: storage_permission
+            && dictionary_installed
+            && source_configured
+            && local_dirs_present,
+    }
+}
+
+async fn onboarding_handler(data_dir: PathBuf, local_dirs: Vec<PathBuf>) -> impl IntoResponse {
+    match tokio::task::spawn_blocking(move || onboarding_status(&data_dir, &local_dirs)).await {
+        Ok(status) => axum::Json(status).into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to read onboarding state: {err}"),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct SubsystemStatus {
+    name: &'static str,
+    enabled: bool,
+    healthy: bool,
+}
+
+#[derive(Serialize, Debug)]
+struct SubsystemsResponse {
+    subsystems: Vec<SubsystemStatus>,
+}
+
+const SUBSYSTEM_PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Loopback URL of our own server. A wildcard bind address is not something we can connect to.
+fn local_probe_base_url(host: Ipv4Addr, port: u16) -> String {
+    if host.is_unspecified() {
+        format!("http://127.0.0.1:{port}")
+    } else {
+        format!("http://{host}:{port}")
+    }
+}
+
+/// Probes `GET /api/<name>/version` of every enabled subserver through the main listener.
+/// Disabled subservers are reported without a request.
+async fn probe_subsystems(
+    base_url: &str,
+    subsystems: &[(&'static str, bool)],
+) -> Vec<SubsystemStatus> {
+    let client = Client::builder()
+        .timeout(SUBSYSTEM_PROBE_TIMEOUT)
+        .build()
+        .unwrap_or_else(|_| Client::new());
+    let probes = subsystems.iter().map(|&(name, enabled)| {
+        let client = client.clone();
+        let url = format!("{base_url}/api/{name}/version");
+        async move {
+            let healthy = enabled
+                && client
+                    .get(&url)
+                    .send()
+                    .await
+                    .is_ok_and(|resp| resp.status().is_success());
+            SubsystemStatus {
+                name,
+                enabled,
+                healthy,
+            }
+        }
+    });
+    futures::future::join_all(probes).await
+}
+
+async fn subsystems_handler(
+    base_url: String,
+    subsystems: Vec<(&'static str, bool)>,
+) -> impl IntoResponse {
+    axum::Json(SubsystemsResponse {
+        subsystems: probe_subsystems(&base_url, &subsystems).await,
+    })
+}
+
+/// Builds a subserver router, or a stand-in answering `503 Subsystem disabled` for every path
+/// when the subserver was switched off. Disabled subservers are never constructed.
+fn subsystem_router(name: &'static str, enabled: bool, build: impl FnOnce() -> Router) -> Router {
+    if enabled {
+        return build();
+    }
+    info!("⏸️ {name} subsystem disabled");
+    Router::new().fallback(move || async move {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            format!("Subsystem disabled: {name}"),
+        )
+    })
+}
+
+/// Wraps `router` in a per-request access log when enabled. Only the path is recorded: query
+/// strings carry the text being looked up, and bodies are never logged.
+fn with_access_log(router: Router, enabled: bool) -> Router {
+    if !enabled {
+        return router;
+    }
+    router.layer(
+        TraceLayer::new_for_http()
+            .make_span_with(|req: &axum::http::Request<axum::body::Body>| {
+                tracing::debug_span!("http", method = %req.method(), path = %req.uri().path())
+            })
+            .on_request(DefaultOnRequest::new().level(Level::DEBUG))
+            .on_response(
+                DefaultOnResponse::new()
+                    .level(Level::DEBUG)
+                    .latency_unit(LatencyUnit::Millis),
+            ),
+    )
+}
+
+/// The path to re-exec after an update. On Linux, `current_exe` of a binary replaced on disk
+/// reports the old inode as `<path> (deleted)`.
+fn relaunch_exe_path(exe_path: &Path) -> PathBuf {
+    let exe_str = exe_path.to_string_lossy();
+    match exe_str.strip_suffix(" (deleted)") {
+        Some(stripped) if cfg!(target_os = "linux") => PathBuf::from(stripped),
+        _ => exe_path.to_path_buf(),
+    }
+}
+
+/// Spawns a fresh copy of this binary with the same arguments. The caller is expected to exit.
+fn relaunch_current_exe(manatan_pid_path: &Path) -> std::io::Result<()> {
+    // The new process must not mistake us for a running instance.
+    let _ = fs::remove_file(manatan_pid_path);
+    let exe_path = relaunch_exe_path(&std::env::current_exe()?);
+    std::process::Command::new(exe_path)
+        .args(env::args_os().skip(1))
+        .spawn()?;
+    Ok(())
+}
+
+/// Raised by `/api/system/restart`. `run_server` stops as it would on a shutdown signal (graceful
+/// drain, Suwayomi killed) and only then relaunches and exits.
+#[derive(Default)]
+struct RestartRequest {
+    requested: AtomicBool,
+    notify: tokio::sync::Notify,
+}
+
+async fn restart_app_handler(restart: Arc<RestartRequest>) -> impl IntoResponse {
+    info!("🔁 Restart requested via /api/system/restart");
+    restart.requested.store(true, Ordering::SeqCst);
+    // A stored permit, so the request isn't lost if the shutdown future hasn't polled yet.
+    restart.notify.notify_one();
+    (StatusCode::ACCEPTED, "Restarting")
+}
+
+async fn openapi_handler() -> impl IntoResponse {
+    ([(CONTENT_TYPE, "application/json")], OPENAPI_JSON)
+}
+
 fn is_flatpak() -> bool {
     std::env::var("FLATPAK_ID").is_ok()
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::Ipv4Addr,
+        path::{Path, PathBuf},
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use axum::{Router, extract::State, response::IntoResponse, routing::get};
+    use clap::CommandFactory;
+
+    use super::{
+        Cli, CloseAction, JAR_BYTES, OPENAPI_JSON, RestartRequest, UpdateStatus, browser_ready,
+        current_version_handler, ensure_runtime_bridge_available, is_spa_excluded,
+        local_probe_base_url, next_update_check_delay, onboarding_status, probe_subsystems,
+        react_app_response, relaunch_exe_path, resolve_path_option, restart_app_handler,
+        running_instance_pid, spa_excluded_prefixes, subsystem_router, suwayomi_config_args,
+        suwayomi_http_base_url, system_info_handler, update_check_interval, webui_asset,
+        with_access_log,
+    };
+
+    #[test]
+    fn openapi_document_lists_lookup_route() {
+        let doc: serde_json::Value =
+            serde_json::from_str(OPENAPI_JSON).expect("openapi.json should be valid JSON");
+        assert!(doc["openapi"].is_string());
+        assert!(doc["paths"]["/api/yomitan/lookup"]["get"].is_object());
+        assert!(doc["paths"]["/api/sync/merge"]["post"].is_object());
+    }
+
+    #[test]
+    fn openapi_document_lists_control_and_media_routes() {
+        let doc: serde_json::Value =
+            serde_json::from_str(OPENAPI_JSON).expect("openapi.json should be valid JSON");
+        for (path, method) in [
+            ("/api/yomitan/import/cancel", "post"),
+            ("/api/yomitan/install-defaults", "post"),
+            ("/api/yomitan/dict-media/{dict_name}/{path}", "get"),
+            ("/api/system/storage", "get"),
+            ("/api/system/download-update", "post"),
+            ("/api/system/install-update", "post"),
+            ("/api/ocr/models", "get"),
+            ("/api/ocr/model", "post"),
+            ("/api/novel/version", "get"),
+        ] {
+            assert!(
+                doc["paths"][path][method].is_object(),
+                "{method} {path} is missing from openapi.json"
+            );
+        }
+    }
+
+    #[test]
+    fn configured_suwayomi_port_reaches_args_and_runtime_url() {
+        let args = suwayomi_config_args(
+            Path::new("/data"),
+            Path::new("/data/local-anime"),
+            "127.0.0.1",
+            4666,
+            true,
+        );
+        assert!(args.contains(&"-Dsuwayomi.tachidesk.config.server.port=4666".to_string()));
+        assert!(args.contains(&"-Dsuwayomi.tachidesk.config.server.ip=127.0.0.1".to_string()));
+        assert_eq!(
+            suwayomi_http_base_url("127.0.0.1", 4666),
+            "http://127.0.0.1:4666"
+        );
+    }
+
+    #[test]
+    fn config_file_fills_defaults_and_cli_wins() {
+        let temp = tempfile::tempdir().expect("temp dir");
+        let path = temp.path().join("manatan.toml");
+        std::fs::write(
+            &path,
+            r#"
+host = "127.0.0.1"
+port = 5000
+downloads_path = "dl"
+aidoku_enabled = false
+tracker_search_ttl_seconds = 60
+"#,
+        )
+        .expect("write config");
+        let config_arg = path.to_string_lossy().into_owned();
+
+        let matches = Cli::command()
+            .try_get_matches_from(["manatan", "--config", &config_arg, "--port", "6000"])
+            .expect("args should parse");
+        let cli = Cli::from_matches(&matches).expect("config should load");
+
+        assert_eq!(cli.host, Ipv4Addr::LOCALHOST);
+        assert_eq!(cli.port, 6000);
+        assert_eq!(cli.downloads_path.as_deref(), Some(Path::new("dl")));
+        assert!(!cli.aidoku_enabled);
+        assert_eq!(cli.tracker_search_ttl_seconds, 60);
+        assert!(cli.runtime_only);
+        assert_eq!(cli.suwayomi_port, super::SUWAYOMI_PORT);
+
+        std::fs::write(&path, "unknown_key = 1\n").expect("write config");
+        let matches = Cli::command()
+            .try_get_matches_from(["manatan", "--config", &config_arg])
+            .expect("args should parse");
+        assert!(Cli::from_matches(&matches).is_err());
+    }
+
+    fn resolve_downloads(option: Option<&PathBuf>, allow_external: bool) -> PathBuf {
+        PathBuf::from(resolve_path_option(
+            option,
+            Path::new("/data/manatan"),
+            "downloads",
+            allow_external,
+        ))
+    }
+
+    #[test]
+    fn relative_path_options_stay_under_data_dir() {
+        let data_dir = Path::new("/data/manatan");
+        let relative = PathBuf::from("./media/../downloads/manga");
+        assert_eq!(
+            resolve_downloads(Some(&relative), false),
+            data_dir.join("downloads").join("manga")
+        );
+        assert_eq!(resolve_downloads(None, false), data_dir.join("downloads"));
+    }
+
+    #[test]
+    fn traversal_path_options_are_flagged() {
+        let escaping = PathBuf::from("../../elsewhere");
+        assert_eq!(
+            resolve_downloads(Some(&escaping), false),
+            Path::new("/data/manatan").join("downloads")
+        );
+        assert_eq!(
+            resolve_downloads(Some(&escaping), true),
+            Path::new("/").join("elsewhere")
+        );
+    }
+
+    #[tokio::test]
+    async fn subservers_write_under_their_own_subdirs() {
+        let temp = tempfile::tempdir().expect("temp dir");
+        let data_dir = temp.path().to_path_buf();
+
+        let _ocr = manatan_ocr_server::create_router(data_dir.clone());
+        let _yomitan = manatan_yomitan_server::create_router(data_dir.clone());
+        let _audio = manatan_audio_server::create_router(data_dir.clone());
+        let _sync = manatan_sync_server::create_router(data_dir.clone());
+        let _novel =
+            manatan_novel_server::create_router(data_dir.clone(), data_dir.join("local-novel"));
+
+        let mut root_entries: Vec<String> = std::fs::read_dir(&data_dir)
+            .expect("read data dir")
+            .map(|entry| {
+                entry
+                    .expect("entry")
+                    .file_name()
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .filter(|name| name != "local-novel")
+            .collect();
+        root_entries.sort();
+        let mut expected = [
+            manatan_audio_server::STORAGE_DIR_NAME,
+            manatan_novel_server::STORAGE_DIR_NAME,
+            manatan_ocr_server::STORAGE_DIR_NAME,
+            manatan_sync_server::STORAGE_DIR_NAME,
+            manatan_yomitan_server::STORAGE_DIR_NAME,
+        ];
+        expected.sort_unstable();
+        assert_eq!(root_entries, expected);
+    }
+
+    #[test]
+    fn live_pid_file_is_detected_as_running() {
+        let temp = tempfile::tempdir().expect("temp dir");
+        let pid_path = temp.path().join("manatan.pid");
+        std::fs::write(&pid_path, std::process::id().to_string()).expect("write pid file");
+
+        // The test binary is itself a `manatan` process, so it stands in for another instance.
+        assert_eq!(
+            running_instance_pid(&pid_path, 0),
+            i32::try_from(std::process::id()).ok()
+        );
+        assert!(pid_path.exists());
+
+        // Our own pid is never reported as another instance.
+        assert_eq!(running_instance_pid(&pid_path, std::process::id()), None);
+        assert!(!pid_path.exists());
+    }
+
+    #[test]
+    fn stale_pid_file_is_cleaned() {
+        let temp = tempfile::tempdir().expect("temp dir");
+        let pid_path = temp.path().join("manatan.pid");
+        std::fs::write(&pid_path, i32::MAX.to_string()).expect("write pid file");
+
+        assert_eq!(running_instance_pid(&pid_path, std::process::id()), None);
+        assert!(!pid_path.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reused_pid_is_not_mistaken_for_an_instance() {
+        let mut child = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .expect("spawn sleep");
+        let temp = tempfile::tempdir().expect("temp dir");
+        let pid_path = temp.path().join("manatan.pid");
+        std::fs::write(&pid_path, child.id().to_string()).expect("write pid file");
+
+        let running = running_instance_pid(&pid_path, std::process::id());
+        let _ = child.kill();
+        let _ = child.wait();
+
+        assert_eq!(running, None);
+        assert!(!pid_path.exists());
+    }
+
+    #[test]
+    fn close_action_follows_keep_server_flag() {
+        assert_eq!(CloseAction::from_flag(false), CloseAction::Terminate);
+        assert_eq!(CloseAction::from_flag(true), CloseAction::Detach);
+
+        let matches = Cli::command()
+            .try_get_matches_from(["manatan", "--keep-server-on-close"])
+            .expect("args should parse");
+        let cli = Cli::from_matches(&matches).expect("cli should load");
+        assert_eq!(
+            CloseAction::from_flag(cli.keep_server_on_close),
+            CloseAction::Detach
+        );
+    }
+
+    #[test]
+    fn browser_waits_for_runtime_only_when_asked() {
+        assert!(browser_ready(true, None));
+        assert!(!browser_ready(false, None));
+        assert!(browser_ready(true, Some(true)));
+        assert!(!browser_ready(true, Some(false)));
+        assert!(!browser_ready(false, Some(true)));
+    }
+
+    #[test]
+    fn zeroed_jar_is_rewritten_from_embedded_bytes() {
+        let temp = tempfile::tempdir().expect("temp dir");
+        let dir = temp.path();
+        let jar_path = dir.join("Suwayomi-Server.jar");
+        std::fs::write(&jar_path, b"").expect("zero jar");
+
+        let restored = crate::io::ensure_extracted(dir, "Suwayomi-Server.jar", JAR_BYTES)
+            .expect("jar should be restored");
+        assert_eq!(restored, jar_path);
+        assert_eq!(std::fs::read(&jar_path).expect("read jar"), JAR_BYTES);
+    }
+
+    #[test]
+    fn same_size_outdated_jar_is_rewritten() {
+        let temp = tempfile::tempdir().expect("temp dir");
+        let dir = temp.path();
+        let jar_path = dir.join("Suwayomi-Server.jar");
+        std::fs::write(&jar_path, vec![0u8; JAR_BYTES.len()]).expect("write outdated jar");
+
+        crate::io::ensure_extracted(dir, "Suwayomi-Server.jar", JAR_BYTES)
+            .expect("jar should be rewritten");
+        assert_eq!(std::fs::read(&jar_path).expect("read jar"), JAR_BYTES);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn intact_jar_is_not_rewritten() {
+        use std::os::unix::fs::MetadataExt;
+
+        let temp = tempfile::tempdir().expect("temp dir");
+        let dir = temp.path();
+        let jar_path = dir.join("Suwayomi-Server.jar");
+        std::fs::write(&jar_path, JAR_BYTES).expect("write jar");
+        let inode = std::fs::metadata(&jar_path).expect("stat jar").ino();
+
+        crate::io::ensure_extracted(dir, "Suwayomi-Server.jar", JAR_BYTES).expect("jar is intact");
+        assert_eq!(std::fs::metadata(&jar_path).expect("stat jar").ino(), inode);
+    }
+
+    #[test]
+    fn zip_entries_escaping_target_are_rejected() {
+        use std::io::Write;
+
+        use zip::{ZipWriter, write::SimpleFileOptions};
+
+        let mut bytes = Vec::new();
+        {
+            let mut zip = ZipWriter::new(std::io::Cursor::new(&mut bytes));
+            zip.start_file("../evil", SimpleFileOptions::default())
+                .expect("start file");
+            zip.write_all(b"pwned").expect("write file");
+            zip.finish().expect("finish zip");
+        }
+
+        let temp = tempfile::tempdir().expect("temp dir");
+        let base = temp.path();
+        let target = base.join("target");
+        let err = crate::io::extract_zip(&bytes, &target).expect_err("traversal must be rejected");
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert!(!base.join("evil").exists());
+    }
+
+    #[tokio::test]
+    async fn restart_request_signals_shutdown_instead_of_exiting() {
+        let restart = Arc::new(RestartRequest::default());
+        let response = restart_app_handler(Arc::clone(&restart))
+            .await
+            .into_response();
+        assert_eq!(response.status(), axum::http::StatusCode::ACCEPTED);
+        assert!(restart.requested.load(std::sync::atomic::Ordering::SeqCst));
+        // The permit is stored, so a shutdown future polled after the request still fires.
+        tokio::time::timeout(Duration::from_secs(1), restart.notify.notified())
+            .await
+            .expect("restart should wake the server's shutdown future");
+    }
+
+    #[tokio::test]
+    async fn bridge_preflight_respects_short_timeout() {
+        // Nothing listens on port 9 locally, so every health probe fails fast.
+        let started = std::time::Instant::now();
+        let err = ensure_runtime_bridge_available(
+            "http://127.0.0.1:9",
+            Duration::from_millis(300),
+            Duration::from_millis(50),
+        )
+        .await
+        .expect_err("preflight should time out");
+        assert!(err.to_string().contains("timed out"));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn bridge_preflight_bounds_a_probe_that_never_answers() {
+        // Accept connections but never reply, like a runtime wedged mid-startup.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind listener");
+        let addr = listener.local_addr().expect("listener addr");
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                held.push(stream);
+            }
+        });
+
+        let started = std::time::Instant::now();
+        let err = ensure_runtime_bridge_available(
+            &format!("http://{addr}"),
+            Duration::from_millis(300),
+            Duration::from_secs(60),
+        )
+        .await
+        .expect_err("preflight should time out");
+        assert!(err.to_string().contains("timed out"));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn desktop_version_reports_update_status() {
+        let status = Arc::new(Mutex::new(UpdateStatus::RestartRequired));
+        let response = current_version_handler(State(status)).await.into_response();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("read version body");
+        let json: serde_json::Value = serde_json::from_slice(&body).expect("version json");
+        assert_eq!(json["variant"], "desktop");
+        assert_eq!(json["update_status"], "ready");
+    }
+
+    #[tokio::test]
+    async fn system_info_reports_build_metadata() {
+        let response = system_info_handler().await.into_response();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("read info body");
+        let json: serde_json::Value = serde_json::from_slice(&body).expect("info json");
+        assert_eq!(json["version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(json["variant"], "desktop");
+        assert!(json["target"].is_string());
+        assert!(json["features"].is_array());
+    }
+
+    #[test]
+    fn periodic_update_checks_are_opt_in_and_jittered() {
+        assert_eq!(update_check_interval(0), None);
+        let interval = update_check_interval(6).expect("6h interval");
+        assert_eq!(interval, Duration::from_secs(6 * 3600));
+
+        let max = interval + interval / 10;
+        for seed in [0, 1, 777, u64::MAX] {
+            let delay = next_update_check_delay(interval, seed);
+            assert!(delay >= interval && delay <= max, "{delay:?}");
+        }
+        assert_ne!(
+            next_update_check_delay(interval, 1),
+            next_update_check_delay(interval, 2)
+        );
+    }
+
+    #[test]
+    fn relaunch_path_drops_linux_deleted_suffix() {
+        let replaced = Path::new("/opt/manatan/manatan (deleted)");
+        let expected = if cfg!(target_os = "linux") {
+            PathBuf::from("/opt/manatan/manatan")
+        } else {
+            replaced.to_path_buf()
+        };
+        assert_eq!(relaunch_exe_path(replaced), expected);
+        assert_eq!(
+            relaunch_exe_path(Path::new("/opt/manatan/manatan")),
+            PathBuf::from("/opt/manatan/manatan")
+        );
+    }
+
+    #[tokio::test]
+    async fn access_log_layer_passes_requests_through() {
+        let router = with_access_log(Router::new().route("/ping", get(|| async { "pong" })), true);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind test listener");
+        let addr = listener.local_addr().expect("listener addr");
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        let response = reqwest::get(format!("http://{addr}/ping?text=private"))
+            .await
+            .expect("request through access log");
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        assert_eq!(response.text().await.expect("body"), "pong");
+    }
+
+    #[test]
+    fn onboarding_is_incomplete_without_dictionaries() {
+        let temp = tempfile::tempdir().expect("temp dir");
+        let dir = temp.path();
+        let local_dir = dir.join("local-manga");
+        std::fs::create_dir_all(&local_dir).expect("create local dir");
+
+        let status = onboarding_status(dir, &[local_dir]);
+        assert!(!status.dictionary_installed);
+        assert!(status.local_dirs_present);
+        assert!(!status.complete);
+    }
+
+    fn fake_webui(path: &str) -> Option<std::borrow::Cow<'static, [u8]>> {
+        match path {
+            "index.html" => Some(
+                b"<html><head></head><body>app</body></html>"
+                    .as_slice()
+                    .into(),
+            ),
+            "assets/app.js" => Some(b"console.log(1)".as_slice().into()),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn unknown_api_path_returns_json_not_found() {
+        let response = react_app_response("/api/foo", &spa_excluded_prefixes(), fake_webui);
+        assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("read body");
+        let json: serde_json::Value = serde_json::from_slice(&body).expect("json body");
+        assert_eq!(json["error"], "not_found");
+    }
+
+    #[tokio::test]
+    async fn spa_route_returns_index_html() {
+        let response = react_app_response("/library", &spa_excluded_prefixes(), fake_webui);
+        assert_eq!(response.status(), axum::http::StatusCode::OK);
+        assert_eq!(
+            response.headers()[axum::http::header::CONTENT_TYPE],
+            "text/html"
+        );
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("read body");
+        let html = String::from_utf8(body.to_vec()).expect("utf8 body");
+        assert!(html.contains("<base href=\"/\" />"));
+        assert!(html.contains("app"));
+
+        let asset = react_app_response("/assets/app.js", &spa_excluded_prefixes(), fake_webui);
+        assert_eq!(asset.status(), axum::http::StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn webui_dir_override_wins_over_embedded_assets() {
+        let temp = tempfile::tempdir().expect("temp dir");
+        let dir = temp.path();
+        std::fs::write(
+            dir.join("index.html"),
+            "<html><head></head><body>dev build</body></html>",
+        )
+        .expect("write index.html");
+
+        let response = react_app_response("/library", &spa_excluded_prefixes(), |path| {
+            webui_asset(Some(dir), path, fake_webui)
+        });
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("read body");
+        let html = String::from_utf8(body.to_vec()).expect("utf8 body");
+        assert!(html.contains("dev build"));
+        assert!(html.contains("<base href=\"/\" />"));
+
+        // Files the directory lacks still come from the embedded build.
+        assert!(webui_asset(Some(dir), "assets/app.js", fake_webui).is_some());
+        assert!(webui_asset(Some(dir), "../index.html", fake_webui).is_none());
+        assert_eq!(
+            webui_asset(None, "index.html", fake_webui).as_deref(),
+            fake_webui("index.html").as_deref()
+        );
+    }
+
+    #[test]
+    fn spa_exclusions_match_whole_path_segments() {
+        let prefixes = spa_excluded_prefixes();
+        assert!(is_spa_excluded("/api/unknown", &prefixes));
+        assert!(is_spa_excluded("/api/ocr/typo", &prefixes));
+        assert!(is_spa_excluded("/health", &prefixes));
+        assert!(!is_spa_excluded("/apiary", &prefixes));
+        assert!(!is_spa_excluded("/library", &prefixes));
+
+        let response = react_app_response("/health/live", &prefixes, fake_webui);
+        assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn disabled_subsystem_answers_service_unavailable() {
+        let built = std::cell::Cell::new(false);
+        let ocr_router = subsystem_router("ocr", false, || {
+            built.set(true);
+            Router::new().route("/health", get(|| async { "ok" }))
+        });
+        assert!(!built.get());
+
+        let router = Router::new().nest("/api/ocr", ocr_router);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind test listener");
+        let addr = listener.local_addr().expect("listener addr");
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        let response = reqwest::get(format!("http://{addr}/api/ocr/health"))
+            .await
+            .expect("request disabled subsystem");
+        assert_eq!(response.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(
+            response.text().await.expect("body"),
+            "Subsystem disabled: ocr"
+        );
+    }
+
+    #[tokio::test]
+    async fn subsystem_probe_reports_disabled_and_healthy_subservers() {
+        let router = Router::new()
+            .nest("/api/ocr", subsystem_router("ocr", false, Router::new))
+            .nest(
+                "/api/yomitan",
+                subsystem_router("yomitan", true, || {
+                    Router::new().route("/version", get(|| async { "{}" }))
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind test listener");
+        let addr = listener.local_addr().expect("listener addr");
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        let statuses = probe_subsystems(
+            &local_probe_base_url(Ipv4Addr::UNSPECIFIED, addr.port()),
+            &[("ocr", false), ("yomitan", true)],
+        )
+        .await;
+        assert_eq!(statuses.len(), 2);
+        assert_eq!(statuses[0].name, "ocr");
+        assert!(!statuses[0].enabled);
+        assert!(!statuses[0].healthy);
+        assert_eq!(statuses[1].name, "yomitan");
+        assert!(statuses[1].enabled);
+        assert!(statuses[1].healthy);
+    }
+}
@@ -6,4 +6,27 @@ fn main() {
     println!("cargo:rerun-if-env-changed=MANATAN_GOOGLE_OAUTH_BROKER_ENDPOINT");
     let broker_endpoint = std::env::var("MANATAN_GOOGLE_OAUTH_BROKER_ENDPOINT").unwrap_or_default();
     println!("cargo:rustc-env=MANATAN_GOOGLE_OAUTH_BROKER_ENDPOINT_COMPILED={broker_endpoint}");
+
+    // Revision of the bundled webui.tar; bump alongside the asset or override at build time.
+    println!("cargo:rerun-if-env-changed=MANATAN_WEBUI_REVISION");
+    let webui_revision = std::env::var("MANATAN_WEBUI_REVISION")
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| "r2643".to_string());
+    println!("cargo:rustc-env=MANATAN_WEBUI_REVISION_COMPILED={webui_revision}");
+
+    // Target triple of this build; only build scripts see `TARGET`, so forward it.
+    let target = std::env::var("TARGET").unwrap_or_default();
+    println!("cargo:rustc-env=MANATAN_BUILD_TARGET_COMPILED={target}");
+
+    // Commit the app was built from. CI sets `GITHUB_SHA`; local builds may pass
+    // `MANATAN_GIT_COMMIT` explicitly. Empty when neither is set.
+    println!("cargo:rerun-if-env-changed=MANATAN_GIT_COMMIT");
+    println!("cargo:rerun-if-env-changed=GITHUB_SHA");
+    let commit = std::env::var("MANATAN_GIT_COMMIT")
+        .or_else(|_| std::env::var("GITHUB_SHA"))
+        .map(|value| value.trim().to_string())
+        .unwrap_or_default();
+    println!("cargo:rustc-env=MANATAN_GIT_COMMIT_COMPILED={commit}");
 }
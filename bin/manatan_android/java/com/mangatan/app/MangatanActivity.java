@@ -13,6 +13,9 @@ public class MangatanActivity extends NativeActivity {
         System.loadLibrary("manatan_android");
     }
 
+    // Drains the embedded web server before the process is killed.
+    static native void stopWebServer();
+
     @Override
     protected void onCreate(Bundle savedInstanceState) {
         super.onCreate(savedInstanceState);
@@ -55,6 +58,7 @@ public class MangatanActivity extends NativeActivity {
     @Override
     public void onDestroy() {
         AnkiBridge.stopAnkiConnectServer();
+        stopWebServer();
         Intent serviceIntent = new Intent(this, MangatanService.class);
         stopService(serviceIntent);
         android.os.Process.killProcess(android.os.Process.myPid());
@@ -103,6 +103,7 @@ public class MangatanService extends Service {
 
     private void stopAppAndServer() {
         stopForeground(true);
+        MangatanActivity.stopWebServer();
         stopSelf();
         Process.killProcess(Process.myPid());
         System.exit(0);
@@ -0,0 +1,934 @@
+//! Platform-independent helpers used by the Android app. Kept out of the Android-only module so
+//! their tests run on the host.
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use axum::{Json, http::StatusCode, response::IntoResponse};
+use serde::Serialize;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use tracing::{error, info, warn};
+
+pub(crate) const TACHI_DATA_DIR_NAME: &str = "tachidesk_data";
+
+/// Writes a probe file into `dir` and reads it back. Shared storage created without All-Files
+/// access can accept the write but hand back nothing (or fail) on read.
+pub(crate) fn verify_dir_readback(dir: &Path) -> io::Result<()> {
+    const PROBE: &[u8] = b"manatan-access-check";
+    let probe = dir.join(".manatan-access-check");
+    fs::write(&probe, PROBE)?;
+    let read_back = fs::read(&probe);
+    let _ = fs::remove_file(&probe);
+    if read_back? != PROBE {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "probe file content did not survive a read-back",
+        ));
+    }
+    Ok(())
+}
+
+/// Builds a `-D<name>=<path>` JVM option. JNI options are passed as whole C strings, so spaces and
+/// `=` in the value need no escaping; control characters (including NUL, which `CString` rejects)
+/// would corrupt the option and are refused.
+pub(crate) fn jvm_path_property(name: &str, path: &Path) -> Option<String> {
+    let value = path.to_string_lossy();
+    if value.chars().any(char::is_control) {
+        error!("Refusing JVM property {name}: path contains control characters ({value:?})");
+        return None;
+    }
+    if value.contains('"') {
+        warn!("JVM property {name} contains a quote; Suwayomi may misread it: {value}");
+    }
+    Some(format!("-D{name}={value}"))
+}
+
+/// Parses a comma-separated `MANATAN_MIGRATION_SKIP` value into entry names.
+pub(crate) fn parse_migration_skip_list(raw: Option<&str>) -> Vec<String> {
+    raw.unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+pub(crate) fn should_skip_app_data_entry(name: &str, extra_skips: &[String]) -> bool {
+    matches!(
+        name,
+        // These are migrated/configured elsewhere.
+        "local-manga" | "local-anime" | "local-novel" | "local-sources" |
+        // This is migrated separately so it lives in shared root.
+        TACHI_DATA_DIR_NAME
+    ) || extra_skips.iter().any(|skip| skip == name)
+}
+
+/// Pulls the revision out of a jar manifest's `Implementation-Version`, e.g.
+/// `v2.1.1867-r2643` → `r2643`. Falls back to the whole version string.
+pub(crate) fn manifest_revision(manifest: &str) -> Option<String> {
+    let version = manifest
+        .lines()
+        .find_map(|line| line.strip_prefix("Implementation-Version:"))?
+        .trim();
+    if version.is_empty() {
+        return None;
+    }
+    let revision = version.rsplit(['-', ' ', '+']).find(|part| {
+        part.strip_prefix('r')
+            .is_some_and(|digits| !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()))
+    });
+    Some(revision.unwrap_or(version).to_string())
+}
+
+/// Appends `line`, evicting the oldest lines once `capacity` is reached.
+pub(crate) fn push_log_line(logs: &mut VecDeque<String>, line: String, capacity: usize) {
+    while logs.len() >= capacity.max(1) {
+        logs.pop_front();
+    }
+    logs.push_back(line);
+}
+
+/// Writes `lines` to `<dir>/manatan-<timestamp>.log` and returns the file's path.
+pub(crate) fn write_log_file(dir: &Path, lines: &[String], timestamp: u64) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let path = dir.join(format!("manatan-{timestamp}.log"));
+    let mut content = lines.join("\n");
+    content.push('\n');
+    fs::write(&path, content)?;
+    Ok(path)
+}
+
+/// Debounces the health poller so a single failed probe (e.g. during a JVM GC pause) doesn't
+/// flip the WebView back to the loading screen.
+pub(crate) struct HealthHysteresis {
+    failure_threshold: u32,
+    consecutive_failures: u32,
+    ready: bool,
+}
+
+impl HealthHysteresis {
+    pub(crate) fn new(failure_threshold: u32) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            consecutive_failures: 0,
+            ready: false,
+        }
+    }
+
+    /// Consecutive failures needed to drop readiness, after clamping to at least one.
+    pub(crate) fn failure_threshold(&self) -> u32 {
+        self.failure_threshold
+    }
+
+    /// Records a probe result and returns the new readiness when it changes.
+    pub(crate) fn observe(&mut self, healthy: bool) -> Option<bool> {
+        if healthy {
+            self.consecutive_failures = 0;
+            if !self.ready {
+                self.ready = true;
+                return Some(true);
+            }
+            return None;
+        }
+
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        if self.ready && self.consecutive_failures >= self.failure_threshold {
+            self.ready = false;
+            return Some(false);
+        }
+        None
+    }
+}
+
+/// Error details the health poller cares about; implemented for `reqwest::Error` and mockable
+/// in tests.
+pub(crate) trait HealthProbeError {
+    fn is_connect(&self) -> bool;
+    fn is_timeout(&self) -> bool;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum HealthProbe {
+    Healthy,
+    HttpStatus(u16),
+    ConnectFailed,
+    TimedOut,
+    OtherError,
+}
+
+impl HealthProbe {
+    pub(crate) fn classify<E: HealthProbeError>(result: Result<u16, E>) -> Self {
+        match result {
+            Ok(status) if (200..300).contains(&status) => HealthProbe::Healthy,
+            Ok(status) => HealthProbe::HttpStatus(status),
+            Err(err) if err.is_connect() => HealthProbe::ConnectFailed,
+            Err(err) if err.is_timeout() => HealthProbe::TimedOut,
+            Err(_) => HealthProbe::OtherError,
+        }
+    }
+
+    /// Whether the probe counts as healthy. Timeouts count as failures like any other error, so
+    /// a server that stops answering still loses readiness once the hysteresis threshold is hit;
+    /// a short stall (e.g. a paused JVM) stays below it.
+    pub(crate) fn verdict(self) -> bool {
+        match self {
+            HealthProbe::Healthy => true,
+            HealthProbe::HttpStatus(_)
+            | HealthProbe::ConnectFailed
+            | HealthProbe::TimedOut
+            | HealthProbe::OtherError => false,
+        }
+    }
+
+    pub(crate) fn describe(self) -> String {
+        match self {
+            HealthProbe::Healthy => "healthy".to_string(),
+            HealthProbe::HttpStatus(status) => format!("HTTP {status}"),
+            HealthProbe::ConnectFailed => "connection failed".to_string(),
+            HealthProbe::TimedOut => "request timed out".to_string(),
+            HealthProbe::OtherError => "request error".to_string(),
+        }
+    }
+}
+
+pub(crate) fn select_internal_files_dir(
+    internal: Option<PathBuf>,
+    external: impl FnOnce() -> Option<PathBuf>,
+) -> Option<PathBuf> {
+    if internal.is_some() {
+        return internal;
+    }
+    let external = external();
+    if let Some(dir) = &external {
+        warn!(
+            "⚠️ Internal data path unavailable; falling back to external files dir {}",
+            dir.display()
+        );
+    }
+    external
+}
+
+/// Sums file sizes under `path` without following symlinks. Missing or unreadable entries count
+/// as zero. Walks with an explicit stack so deep trees can't overflow the thread's stack.
+pub(crate) fn dir_size(path: &Path) -> u64 {
+    let mut total = 0u64;
+    let mut pending = vec![path.to_path_buf()];
+    while let Some(path) = pending.pop() {
+        let Ok(meta) = fs::symlink_metadata(&path) else {
+            continue;
+        };
+        if meta.is_file() {
+            total = total.saturating_add(meta.len());
+        } else if meta.is_dir()
+            && let Ok(entries) = fs::read_dir(&path)
+        {
+            pending.extend(entries.flatten().map(|entry| entry.path()));
+        }
+    }
+    total
+}
+
+/// Which wgpu backends the launcher GUI should use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum GraphicsBackend {
+    /// Vulkan where available.
+    Primary,
+    /// OpenGL ES.
+    Gl,
+}
+
+/// Picks the wgpu backend for the launcher GUI. `MANATAN_FORCE_GL` skips Vulkan detection
+/// entirely for devices whose drivers claim Vulkan support but render black.
+pub(crate) fn select_graphics_backend(
+    sdk_version: i32,
+    force_gl: bool,
+    detect_vulkan: impl FnOnce() -> bool,
+) -> GraphicsBackend {
+    if force_gl {
+        info!("MANATAN_FORCE_GL set: Forcing OpenGL (GLES) backend.");
+        return GraphicsBackend::Gl;
+    }
+    if sdk_version <= 29 {
+        info!("SDK <= 29: Forcing OpenGL (GLES) backend for maximum compatibility.");
+        return GraphicsBackend::Gl;
+    }
+    info!("SDK > 29: Programmatically detecting best graphics backend...");
+    if detect_vulkan() {
+        info!("Vulkan supported. Using primary backend (Vulkan preferred).");
+        GraphicsBackend::Primary
+    } else {
+        info!("Vulkan not supported or check failed. Forcing OpenGL (GLES) backend.");
+        GraphicsBackend::Gl
+    }
+}
+
+/// First-run checklist for the frontend. `complete` is set once every step is done.
+#[derive(Serialize, Debug)]
+pub(crate) struct OnboardingStatus {
+    storage_permission: bool,
+    dictionary_installed: bool,
+    source_configured: bool,
+    local_dirs_present: bool,
+    complete: bool,
+}
+
+pub(crate) fn onboarding_status(
+    storage_permission: bool,
+    dictionary_installed: bool,
+    tachidesk_dir: Option<&Path>,
+    local_dirs: &[PathBuf],
+) -> OnboardingStatus {
+    // Suwayomi keeps installed extensions as jars under `<rootDir>/extensions`.
+    let source_configured = tachidesk_dir
+        .and_then(|dir| fs::read_dir(dir.join("extensions")).ok())
+        .is_some_and(|entries| {
+            entries
+                .flatten()
+                .any(|entry| entry.path().extension().is_some_and(|ext| ext == "jar"))
+        });
+    let local_dirs_present = local_dirs.iter().all(|dir| dir.is_dir());
+    OnboardingStatus {
+        storage_permission,
+        dictionary_installed,
+        source_configured,
+        local_dirs_present,
+        complete: storage_permission
+            && dictionary_installed
+            && source_configured
+            && local_dirs_present,
+    }
+}
+
+/// Failures surfaced by the update download/install endpoints.
+#[derive(Debug)]
+pub(crate) enum UpdateError {
+    /// Install was requested before any update download was enqueued.
+    NoActiveDownload,
+    /// The download manager has no finished file for the active download yet.
+    DownloadNotReady,
+    /// The downloaded APK could not be read back.
+    Unreadable(String),
+    /// The downloaded APK is not as long as the download manager reported.
+    SizeMismatch { expected: u64, actual: u64 },
+    /// The downloaded APK does not hash to the SHA-256 given in the update request.
+    ChecksumMismatch { expected: String, actual: String },
+    /// A call into the Android framework failed.
+    Jni(String),
+}
+
+impl UpdateError {
+    pub(crate) fn code(&self) -> &'static str {
+        match self {
+            Self::NoActiveDownload => "no_active_download",
+            Self::DownloadNotReady => "download_not_ready",
+            Self::Unreadable(_) => "download_unreadable",
+            Self::SizeMismatch { .. } => "size_mismatch",
+            Self::ChecksumMismatch { .. } => "checksum_mismatch",
+            Self::Jni(_) => "jni_error",
+        }
+    }
+
+    pub(crate) fn status(&self) -> StatusCode {
+        match self {
+            Self::NoActiveDownload | Self::DownloadNotReady => StatusCode::CONFLICT,
+            Self::SizeMismatch { .. } | Self::ChecksumMismatch { .. } => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            Self::Unreadable(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::Jni(_) => StatusCode::BAD_GATEWAY,
+        }
+    }
+}
+
+impl std::fmt::Display for UpdateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoActiveDownload => write!(f, "No active download"),
+            Self::DownloadNotReady => write!(f, "Download URI is null"),
+            Self::Unreadable(err) => write!(f, "Failed to read downloaded APK: {err}"),
+            Self::SizeMismatch { expected, actual } => {
+                write!(f, "Downloaded APK is {actual} bytes, expected {expected}")
+            }
+            Self::ChecksumMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "Downloaded APK has SHA-256 {actual}, expected {expected}"
+                )
+            }
+            Self::Jni(err) => write!(f, "JNI call failed: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for UpdateError {}
+
+impl IntoResponse for UpdateError {
+    fn into_response(self) -> axum::response::Response {
+        let body = json!({ "error": self.code(), "message": self.to_string() });
+        (self.status(), Json(body)).into_response()
+    }
+}
+
+/// Streams the downloaded APK once, checking its length and, when given, its SHA-256 so a
+/// truncated or corrupted download is reported instead of failing inside the installer.
+pub(crate) fn verify_update_apk(
+    mut apk: impl io::Read,
+    expected_size: Option<u64>,
+    expected_sha256: Option<&str>,
+) -> Result<(), UpdateError> {
+    let mut hasher = Sha256::new();
+    let actual_size =
+        io::copy(&mut apk, &mut hasher).map_err(|err| UpdateError::Unreadable(err.to_string()))?;
+    if let Some(expected) = expected_size
+        && actual_size != expected
+    {
+        return Err(UpdateError::SizeMismatch {
+            expected,
+            actual: actual_size,
+        });
+    }
+    if let Some(expected) = expected_sha256 {
+        let actual = format!("{:x}", hasher.finalize());
+        if !actual.eq_ignore_ascii_case(expected.trim()) {
+            return Err(UpdateError::ChecksumMismatch {
+                expected: expected.to_string(),
+                actual,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Parses an HTTP cookie date ("Wed, 21 Oct 2026 07:28:00 GMT", dashes also accepted)
+/// into unix seconds.
+pub(crate) fn parse_cookie_date(raw: &str) -> Option<i64> {
+    let raw = raw.trim();
+    let raw = raw.split_once(',').map_or(raw, |(_, rest)| rest);
+    let mut fields = raw.split([' ', '-']).filter(|f| !f.is_empty());
+
+    let day: i64 = fields.next()?.parse().ok()?;
+    let month = match fields.next()?.get(..3)?.to_ascii_lowercase().as_str() {
+        "jan" => 1,
+        "feb" => 2,
+        "mar" => 3,
+        "apr" => 4,
+        "may" => 5,
+        "jun" => 6,
+        "jul" => 7,
+        "aug" => 8,
+        "sep" => 9,
+        "oct" => 10,
+        "nov" => 11,
+        "dec" => 12,
+        _ => return None,
+    };
+    let mut year: i64 = fields.next()?.parse().ok()?;
+    if year < 100 {
+        year += if year < 70 { 2000 } else { 1900 };
+    }
+    let mut clock = fields.next()?.split(':').map(|p| p.parse::<i64>().ok());
+    let (h, m, sec) = (clock.next()??, clock.next()??, clock.next()??);
+
+    // Days from civil date (Howard Hinnant's algorithm).
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146097 + doe - 719468;
+
+    Some(days * 86400 + h * 3600 + m * 60 + sec)
+}
+
+pub(crate) fn cookie_is_expired(attributes: &[&str], now: i64) -> bool {
+    attributes.iter().any(|attr| {
+        let Some((key, value)) = attr.split_once('=') else {
+            return false;
+        };
+        match key.trim().to_ascii_lowercase().as_str() {
+            "max-age" => value.trim().parse::<i64>().is_ok_and(|age| age <= 0),
+            "expires" => parse_cookie_date(value).is_some_and(|at| at <= now),
+            _ => false,
+        }
+    })
+}
+
+pub(crate) fn parse_suwayomi_cookies(content: &str, now: i64) -> Vec<serde_json::Value> {
+    let mut cookies = Vec::new();
+
+    // Naive XML Parsing for <entry key="domain.index">NAME=VALUE...</entry>
+    for line in content.lines() {
+        let line = line.trim();
+        if !line.starts_with("<entry key=\"") {
+            continue;
+        }
+
+        // Extract Key
+        let key_start = 12;
+        let Some(key_end) = line[key_start..].find('"') else {
+            continue;
+        };
+        let key = &line[key_start..key_start + key_end];
+
+        // Skip metadata keys (e.g. "google.com.size")
+        if key.ends_with(".size") {
+            continue;
+        }
+
+        // Extract Value (between "> and </entry>)
+        let Some(val_start_marker) = line.find("\">") else {
+            continue;
+        };
+        let val_start = val_start_marker + 2;
+        let Some(val_end) = line[val_start..].find("</entry>") else {
+            continue;
+        };
+        let full_value = &line[val_start..val_start + val_end];
+
+        // Parse Cookie String: "NAME=VALUE; expires=...; ..."
+        let parts: Vec<&str> = full_value.split(';').collect();
+        if parts.is_empty() {
+            continue;
+        }
+
+        if cookie_is_expired(&parts[1..], now) {
+            continue;
+        }
+
+        let main_pair = parts[0].trim();
+        // Fixed: changed main_part to main_pair
+        let Some(eq_idx) = main_pair.find('=') else {
+            continue;
+        };
+        let name = &main_pair[..eq_idx];
+        let value = &main_pair[eq_idx + 1..];
+
+        // Infer Domain from Key (remove last .index)
+        // Key: "barmanonymity.shop.0" -> "barmanonymity.shop"
+        let domain = if let Some(dot_idx) = key.rfind('.') {
+            &key[..dot_idx]
+        } else {
+            key
+        };
+
+        cookies.push(json!({
+            "name": name,
+            "value": value,
+            "domain": domain,
+            "path": "/",
+            "secure": full_value.contains("secure"),
+            "httpOnly": full_value.contains("HttpOnly")
+        }));
+    }
+
+    cookies
+}
+
+pub(crate) fn is_allowed_webview_target(target: &str, allowed_hosts: &[String]) -> bool {
+    let Ok(url) = url::Url::parse(target) else {
+        return false;
+    };
+    if !matches!(url.scheme(), "http" | "https") {
+        return false;
+    }
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    allowed_hosts.is_empty()
+        || allowed_hosts
+            .iter()
+            .any(|allowed| host == allowed || host.ends_with(&format!(".{allowed}")))
+}
+
+/// `server.conf` as Suwayomi currently sees it, for troubleshooting local source detection.
+#[derive(Serialize, Debug)]
+pub(crate) struct ServerConfResponse {
+    pub(crate) path: String,
+    pub(crate) values: BTreeMap<String, String>,
+}
+
+/// Flat `key = value` pairs from a Suwayomi `server.conf`. Quotes around string values and
+/// trailing `#` comments are dropped; blocks and multi-line values are skipped.
+pub(crate) fn parse_server_conf(content: &str) -> BTreeMap<String, String> {
+    let mut values = BTreeMap::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with("//") {
+            continue;
+        }
+        let Some((key, raw)) = line.split_once('=') else {
+            continue;
+        };
+        let raw = raw.trim();
+        let value = match raw.strip_prefix('"') {
+            Some(quoted) => match quoted.find('"') {
+                Some(end) => &quoted[..end],
+                None => continue,
+            },
+            None => raw.split('#').next().unwrap_or_default().trim(),
+        };
+        values.insert(key.trim().to_string(), value.to_string());
+    }
+    values
+}
+
+pub(crate) fn read_server_conf(tachidesk_data_dir: &Path) -> io::Result<ServerConfResponse> {
+    let conf_path = tachidesk_data_dir.join("server.conf");
+    let content = fs::read_to_string(&conf_path)?;
+    Ok(ServerConfResponse {
+        path: conf_path.to_string_lossy().to_string(),
+        values: parse_server_conf(&content),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{
+        GraphicsBackend, HealthHysteresis, HealthProbe, HealthProbeError, TACHI_DATA_DIR_NAME,
+        dir_size, is_allowed_webview_target, jvm_path_property, manifest_revision,
+        onboarding_status, parse_migration_skip_list, parse_suwayomi_cookies, push_log_line,
+        read_server_conf, select_graphics_backend, select_internal_files_dir,
+        should_skip_app_data_entry, verify_dir_readback, verify_update_apk, write_log_file,
+    };
+
+    #[test]
+    fn health_hysteresis_ignores_transient_failures() {
+        let mut health = HealthHysteresis::new(3);
+
+        assert_eq!(health.observe(false), None);
+        assert_eq!(health.observe(true), Some(true));
+        assert_eq!(health.observe(false), None);
+        assert_eq!(health.observe(false), None);
+        assert_eq!(health.observe(true), None);
+        assert_eq!(health.observe(false), None);
+        assert_eq!(health.observe(false), None);
+        assert_eq!(health.observe(false), Some(false));
+        assert_eq!(health.observe(false), None);
+        assert_eq!(health.observe(true), Some(true));
+    }
+
+    #[test]
+    fn dir_size_sums_nested_files() {
+        let root = std::env::temp_dir().join(format!(
+            "manatan-android-dir-size-test-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("nested").join("deeper")).expect("create tree");
+        fs::write(root.join("a.bin"), [0u8; 10]).expect("write a");
+        fs::write(root.join("nested").join("b.bin"), [0u8; 20]).expect("write b");
+        fs::write(root.join("nested").join("deeper").join("c.bin"), [0u8; 30])
+            .expect("write c");
+
+        assert_eq!(dir_size(&root), 60);
+        assert_eq!(dir_size(&root.join("nested")), 50);
+        assert_eq!(dir_size(&root.join("missing")), 0);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn dir_size_does_not_follow_symlinks() {
+        let root = std::env::temp_dir().join(format!(
+            "manatan-android-dir-size-symlink-test-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("data")).expect("create data dir");
+        fs::write(root.join("data").join("a.bin"), [0u8; 10]).expect("write a");
+        std::os::unix::fs::symlink(root.join("data"), root.join("data").join("loop"))
+            .expect("create dir symlink");
+        std::os::unix::fs::symlink(root.join("data").join("a.bin"), root.join("link.bin"))
+            .expect("create file symlink");
+
+        assert_eq!(dir_size(&root), 10);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn migration_honors_configured_skip_entries() {
+        let extra = parse_migration_skip_list(Some(" downloads, cache ,,"));
+        assert_eq!(extra, vec!["downloads".to_string(), "cache".to_string()]);
+
+        assert!(should_skip_app_data_entry("downloads", &extra));
+        assert!(should_skip_app_data_entry("cache", &extra));
+        assert!(!should_skip_app_data_entry("manatan.sqlite", &extra));
+
+        // Built-in skips still apply with no configuration.
+        assert!(should_skip_app_data_entry("local-manga", &[]));
+        assert!(should_skip_app_data_entry(TACHI_DATA_DIR_NAME, &[]));
+        assert!(!should_skip_app_data_entry("downloads", &[]));
+    }
+
+    #[test]
+    fn extracts_revision_from_jar_manifest() {
+        let manifest = "Manifest-Version: 1.0\r\n\
+                        Main-Class: suwayomi.tachidesk.MainKt\r\n\
+                        Implementation-Title: Suwayomi-Server\r\n\
+                        Implementation-Version: v2.1.1867-r2643\r\n\r\n";
+        assert_eq!(manifest_revision(manifest).as_deref(), Some("r2643"));
+
+        assert_eq!(
+            manifest_revision("Implementation-Version: 2.1.0\n").as_deref(),
+            Some("2.1.0")
+        );
+        assert_eq!(manifest_revision("Manifest-Version: 1.0\n"), None);
+    }
+
+    struct MockError {
+        connect: bool,
+        timeout: bool,
+    }
+
+    impl HealthProbeError for MockError {
+        fn is_connect(&self) -> bool {
+            self.connect
+        }
+
+        fn is_timeout(&self) -> bool {
+            self.timeout
+        }
+    }
+
+    #[test]
+    fn classifies_health_probe_results() {
+        let err = |connect, timeout| Err::<u16, _>(MockError { connect, timeout });
+
+        assert_eq!(
+            HealthProbe::classify(Ok::<_, MockError>(200)),
+            HealthProbe::Healthy
+        );
+        assert_eq!(
+            HealthProbe::classify(Ok::<_, MockError>(503)),
+            HealthProbe::HttpStatus(503)
+        );
+        assert_eq!(
+            HealthProbe::classify(err(true, false)),
+            HealthProbe::ConnectFailed
+        );
+        // Connect timeouts are connection failures, not slow responses.
+        assert_eq!(
+            HealthProbe::classify(err(true, true)),
+            HealthProbe::ConnectFailed
+        );
+        assert_eq!(
+            HealthProbe::classify(err(false, true)),
+            HealthProbe::TimedOut
+        );
+        assert_eq!(
+            HealthProbe::classify(err(false, false)),
+            HealthProbe::OtherError
+        );
+
+        assert!(HealthProbe::Healthy.verdict());
+        assert!(!HealthProbe::HttpStatus(503).verdict());
+        assert!(!HealthProbe::ConnectFailed.verdict());
+        assert!(!HealthProbe::TimedOut.verdict());
+    }
+
+    #[test]
+    fn zero_failure_threshold_is_clamped_to_one() {
+        let mut health = HealthHysteresis::new(0);
+        assert_eq!(health.failure_threshold(), 1);
+        assert_eq!(health.observe(true), Some(true));
+        assert_eq!(health.observe(false), Some(false));
+    }
+
+    #[test]
+    fn consecutive_timeouts_mark_the_server_unready() {
+        let mut health = HealthHysteresis::new(3);
+        assert_eq!(health.observe(HealthProbe::Healthy.verdict()), Some(true));
+
+        assert_eq!(health.observe(HealthProbe::TimedOut.verdict()), None);
+        assert_eq!(health.observe(HealthProbe::TimedOut.verdict()), None);
+        assert_eq!(health.observe(HealthProbe::TimedOut.verdict()), Some(false));
+    }
+
+    #[test]
+    fn jvm_path_property_keeps_spaces_and_rejects_control_chars() {
+        let spaced = std::path::Path::new("/storage/emulated/0/My Manga=Files");
+        assert_eq!(
+            jvm_path_property("suwayomi.tachidesk.config.server.rootDir", spaced).as_deref(),
+            Some("-Dsuwayomi.tachidesk.config.server.rootDir=/storage/emulated/0/My Manga=Files")
+        );
+
+        let broken = std::path::Path::new("/storage/bad\npath");
+        assert_eq!(jvm_path_property("java.io.tmpdir", broken), None);
+    }
+
+    #[test]
+    fn readback_check_passes_on_writable_dir_and_cleans_up() {
+        let dir = std::env::temp_dir().join(format!("manatan-readback-{}", std::process::id()));
+        fs::create_dir_all(&dir).expect("create temp dir");
+
+        verify_dir_readback(&dir).expect("temp dir should read back");
+        assert_eq!(fs::read_dir(&dir).expect("list dir").count(), 0);
+
+        assert!(verify_dir_readback(&dir.join("missing")).is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn log_buffer_evicts_oldest_lines_at_capacity() {
+        let mut logs = std::collections::VecDeque::new();
+        for i in 0..5 {
+            push_log_line(&mut logs, format!("line {i}"), 3);
+        }
+        assert_eq!(logs, ["line 2", "line 3", "line 4"]);
+
+        // Shrinking the capacity drops everything beyond it on the next push.
+        push_log_line(&mut logs, "line 5".to_string(), 2);
+        assert_eq!(logs, ["line 4", "line 5"]);
+    }
+
+    #[test]
+    fn exported_log_file_contains_buffered_lines() {
+        let dir = std::env::temp_dir().join(format!("manatan-log-export-{}", std::process::id()));
+        let lines = vec!["first line".to_string(), "second line".to_string()];
+
+        let path =
+            write_log_file(&dir.join("logs"), &lines, 1_700_000_000).expect("write log file");
+        assert_eq!(path, dir.join("logs").join("manatan-1700000000.log"));
+        assert_eq!(
+            fs::read_to_string(&path).expect("read log file"),
+            "first line\nsecond line\n"
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn missing_internal_path_falls_back_to_external_files_dir() {
+        let internal = std::path::PathBuf::from("/data/user/0/com.mangatan.app/files");
+        let external =
+            std::path::PathBuf::from("/storage/emulated/0/Android/data/com.mangatan.app/files");
+
+        assert_eq!(
+            select_internal_files_dir(None, || Some(external.clone())),
+            Some(external.clone())
+        );
+        assert_eq!(
+            select_internal_files_dir(Some(internal.clone()), || panic!("not consulted")),
+            Some(internal)
+        );
+        assert_eq!(select_internal_files_dir(None, || None), None);
+    }
+
+    #[test]
+    fn webview_shim_only_accepts_http_targets_on_allowed_hosts() {
+        let any_host = |url: &str| is_allowed_webview_target(url, &[]);
+        assert!(!any_host("file:///sdcard/manga/index.html"));
+        assert!(!any_host("javascript:alert(1)"));
+        assert!(any_host("https://mangadex.org/title/1"));
+
+        let allowed = ["mangadex.org".to_string()];
+        let listed = |url: &str| is_allowed_webview_target(url, &allowed);
+        assert!(listed("https://api.mangadex.org/title/1"));
+        assert!(!listed("https://evilmangadex.org/"));
+        assert!(!listed("https://example.com/"));
+    }
+
+    #[test]
+    fn expired_cookies_are_not_injected() {
+        let content = r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>
+<properties>
+<entry key="example.com.size">2</entry>
+<entry key="example.com.0">old=1; expires=Thu, 01 Jan 1970 00:00:00 GMT; path=/</entry>
+<entry key="example.com.1">fresh=2; expires=Wed, 21 Oct 2026 07:28:00 GMT; path=/</entry>
+</properties>"#;
+
+        let cookies = parse_suwayomi_cookies(content, 1_760_000_000);
+        assert_eq!(cookies.len(), 1);
+        assert_eq!(cookies[0]["name"], "fresh");
+        assert_eq!(cookies[0]["domain"], "example.com");
+    }
+
+    #[test]
+    fn force_gl_skips_vulkan_detection() {
+        let backend = select_graphics_backend(34, true, || panic!("detection must not run"));
+        assert_eq!(backend, GraphicsBackend::Gl);
+
+        assert_eq!(
+            select_graphics_backend(34, false, || true),
+            GraphicsBackend::Primary
+        );
+        assert_eq!(
+            select_graphics_backend(34, false, || false),
+            GraphicsBackend::Gl
+        );
+    }
+
+    #[test]
+    fn onboarding_is_incomplete_without_dictionaries() {
+        let dir = std::env::temp_dir().join(format!("manatan-onboarding-{}", std::process::id()));
+        let local_dir = dir.join("local-manga");
+        fs::create_dir_all(&local_dir).expect("create local dir");
+
+        let status = onboarding_status(true, false, None, &[local_dir]);
+        assert!(status.storage_permission);
+        assert!(!status.dictionary_installed);
+        assert!(status.local_dirs_present);
+        assert!(!status.complete);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn update_apk_verification_checks_size_and_digest() {
+        let apk = b"manatan-apk";
+        let digest = "0d408fc1aaf568c98f2a2b804f30246c1debf257c102e0ee99d413c584ebbfcc";
+
+        assert!(verify_update_apk(&apk[..], Some(11), Some(digest)).is_ok());
+        assert!(verify_update_apk(&apk[..], None, Some(&digest.to_uppercase())).is_ok());
+        assert!(verify_update_apk(&apk[..], None, None).is_ok());
+
+        let err = verify_update_apk(&apk[..5], Some(11), Some(digest)).expect_err("truncated");
+        assert_eq!(err.code(), "size_mismatch");
+        assert_eq!(err.status(), axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+
+        let err = verify_update_apk(&apk[..], None, Some(&"0".repeat(64))).expect_err("mismatch");
+        assert_eq!(err.code(), "checksum_mismatch");
+    }
+
+    #[test]
+    fn server_conf_reports_local_source_paths() {
+        let dir = std::env::temp_dir().join(format!("manatan-server-conf-{}", std::process::id()));
+        fs::create_dir_all(&dir).expect("create conf dir");
+        fs::write(
+            dir.join("server.conf"),
+            concat!(
+                "# Server ip and port bindings\n",
+                "server.ip = \"0.0.0.0\"\n",
+                "server.port = 4567 # default\n",
+                "server.localSourcePath = \"/storage/emulated/0/Manatan/local-sources\" ",
+                "# Autoconfigured by Manatan\n",
+                "server.localAnimeSourcePath = \"/storage/emulated/0/Manatan/local-anime\"\n",
+            ),
+        )
+        .expect("write server.conf");
+
+        let conf = read_server_conf(&dir).expect("read server.conf");
+        assert_eq!(
+            conf.values["server.localSourcePath"],
+            "/storage/emulated/0/Manatan/local-sources"
+        );
+        assert_eq!(
+            conf.values["server.localAnimeSourcePath"],
+            "/storage/emulated/0/Manatan/local-anime"
+        );
+        assert_eq!(conf.values["server.port"], "4567");
+        assert!(conf.path.ends_with("server.conf"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
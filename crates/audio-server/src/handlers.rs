@@ -2,6 +2,7 @@ use std::{collections::HashMap, convert::TryFrom, io::Cursor};
 
 use anyhow::{Context, anyhow};
 use axum::{
+    Json,
     extract::{Query, State},
     http::{HeaderMap, StatusCode},
     response::{IntoResponse, Response},
@@ -87,6 +88,13 @@ struct PesPayload {
     data: Vec<u8>,
 }
 
+pub async fn version_handler() -> Json<serde_json::Value> {
+    Json(serde_json::json!({
+        "name": env!("CARGO_PKG_NAME"),
+        "version": env!("CARGO_PKG_VERSION"),
+    }))
+}
+
 pub async fn clip_handler(
     State(state): State<AppState>,
     headers: HeaderMap,
@@ -1,14 +1,75 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use axum::{Router, routing::post};
+use axum::{
+    Router,
+    routing::{get, post},
+};
 
 mod handlers;
 mod state;
 
+/// Subdirectory of the shared data dir reserved for anything this server writes.
+pub const STORAGE_DIR_NAME: &str = "audio";
+
+/// Returns `data_dir/audio`, creating it if needed.
+pub fn storage_dir(data_dir: &Path) -> PathBuf {
+    manatan_storage::namespaced_dir(data_dir, STORAGE_DIR_NAME, &[])
+}
+
+/// Creates the audio Router. `data_dir` is the shared data dir; the server only ever touches its
+/// `audio/` subdirectory.
 pub fn create_router(data_dir: PathBuf) -> Router {
-    let state = state::AppState::new(data_dir);
+    let state = state::AppState::new(storage_dir(&data_dir));
 
     Router::new()
         .route("/clip", post(handlers::clip_handler))
+        .route("/version", get(handlers::version_handler))
         .with_state(state)
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{Body, to_bytes},
+        http::{Request, StatusCode},
+    };
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn version_route_reports_crate_version() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let response = create_router(dir.path().to_path_buf())
+            .oneshot(
+                Request::builder()
+                    .uri("/version")
+                    .body(Body::empty())
+                    .expect("request"),
+            )
+            .await
+            .expect("response");
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let json: Value = serde_json::from_slice(&body).expect("json");
+        assert_eq!(json["name"], "manatan-audio-server");
+        assert_eq!(json["version"], env!("CARGO_PKG_VERSION"));
+    }
+
+    #[test]
+    fn storage_is_namespaced_under_audio() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let _router = create_router(dir.path().to_path_buf());
+
+        let root_entries: Vec<_> = std::fs::read_dir(dir.path())
+            .expect("read data dir")
+            .map(|entry| entry.expect("entry").file_name())
+            .collect();
+        assert_eq!(root_entries, [STORAGE_DIR_NAME]);
+        assert!(dir.path().join(STORAGE_DIR_NAME).is_dir());
+    }
+}
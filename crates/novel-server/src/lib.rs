@@ -20,6 +20,16 @@ use walkdir::WalkDir;
 
 use crate::types::*;
 
+/// Subdirectory of the shared data dir holding the novel database and uploaded fonts.
+pub const STORAGE_DIR_NAME: &str = "novel";
+
+/// Returns `data_dir/novel`, creating it if needed.
+pub fn storage_dir(data_dir: &Path) -> PathBuf {
+    manatan_storage::namespaced_dir(data_dir, STORAGE_DIR_NAME, &[])
+}
+
+/// Creates the novel Router. `data_dir` is the shared data dir; everything but the books in
+/// `local_novel_path` lives in its `novel/` subdirectory.
 pub fn create_router(data_dir: PathBuf, local_novel_path: PathBuf) -> Router {
     let state = NovelState::new(data_dir, local_novel_path);
 
@@ -268,4 +278,48 @@ mod tests {
         assert!(metadata_root.join(id).join("extracted").exists());
         assert!(local_novel_dir.join(format!("{id}.epub")).exists());
     }
+
+    #[test]
+    fn storage_is_namespaced_under_novel() {
+        let root = tempfile::tempdir().expect("temp dir");
+        let data_dir = root.path().join("data");
+
+        let state = NovelState::new(data_dir.clone(), root.path().join("local-novel"));
+
+        assert_eq!(state.storage_dir, data_dir.join(STORAGE_DIR_NAME));
+        let root_entries: Vec<_> = fs::read_dir(&data_dir)
+            .expect("data dir should exist")
+            .map(|entry| entry.expect("entry should be readable").file_name())
+            .collect();
+        assert_eq!(root_entries, [STORAGE_DIR_NAME]);
+        assert!(state.storage_dir.join("novel.db").exists());
+    }
+
+    #[tokio::test]
+    async fn version_route_reports_crate_version() {
+        use axum::{
+            body::{Body, to_bytes},
+            http::{Request, StatusCode},
+        };
+        use tower::ServiceExt;
+
+        let root = tempfile::tempdir().expect("temp dir");
+        let response = create_router(root.path().join("data"), root.path().join("local-novel"))
+            .oneshot(
+                Request::builder()
+                    .uri("/version")
+                    .body(Body::empty())
+                    .expect("request should build"),
+            )
+            .await
+            .expect("router should respond");
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body should be readable");
+        let json: serde_json::Value = serde_json::from_slice(&body).expect("body should be json");
+        assert_eq!(json["name"], "manatan-novel-server");
+        assert_eq!(json["version"], env!("CARGO_PKG_VERSION"));
+    }
 }
@@ -34,6 +34,14 @@ pub fn router() -> Router<NovelState> {
         .route("/fonts/{filename}", delete(fonts::delete_font))
         .route("/upload/{id}", post(upload_epub))
         .route("/file/{id}", get(get_epub))
+        .route("/version", get(get_version))
+}
+
+async fn get_version() -> Json<serde_json::Value> {
+    Json(serde_json::json!({
+        "name": env!("CARGO_PKG_NAME"),
+        "version": env!("CARGO_PKG_VERSION"),
+    }))
 }
 
 fn discover_pending_epubs(state: &NovelState) -> Result<Vec<DiscoveredEpub>, NovelError> {
@@ -12,8 +12,7 @@ pub struct NovelState {
 
 impl NovelState {
     pub fn new(data_dir: PathBuf, local_novel_path: PathBuf) -> Self {
-        let novel_dir = data_dir.join("novel");
-        std::fs::create_dir_all(&novel_dir).expect("Failed to create novel directory");
+        let novel_dir = crate::storage_dir(&data_dir);
 
         let db_path = novel_dir.join("novel.db");
         let db = sled::open(db_path).expect("Failed to open novel database");
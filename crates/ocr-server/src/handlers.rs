@@ -5,17 +5,20 @@ use std::{
 
 use axum::{
     Json,
+    body::Bytes,
     extract::{Query, State},
     http::StatusCode,
+    response::{IntoResponse, Response},
 };
 use futures::StreamExt;
 use serde::Deserialize;
 use tracing::{info, warn};
 
 use crate::{
-    jobs,
+    image_cache, jobs,
     language::OcrLanguage,
     logic,
+    model::OcrModel,
     state::{AppState, CacheEntry},
 };
 
@@ -45,20 +48,68 @@ pub async fn status_handler(State(state): State<AppState>) -> Json<serde_json::V
         "backend": "Rust (manatan-ocr-server)",
         "requests_processed": state.requests_processed.load(Ordering::Relaxed),
         "items_in_cache": cache_size,
+        "items_in_image_cache": state.image_cache.len(),
+        "model": state.active_model().as_str(),
         "active_jobs": state.active_jobs.load(Ordering::Relaxed),
+        "inferences_in_flight": state.inference_limiter.in_flight(),
+        "inferences_queued": state.inference_limiter.queued(),
     }))
 }
 
+pub async fn version_handler() -> Json<serde_json::Value> {
+    Json(serde_json::json!({
+        "name": env!("CARGO_PKG_NAME"),
+        "version": env!("CARGO_PKG_VERSION"),
+    }))
+}
+
+pub async fn models_handler(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let models: Vec<_> = OcrModel::ALL
+        .iter()
+        .map(|model| {
+            serde_json::json!({
+                "id": model.as_str(),
+                "name": model.display_name(),
+            })
+        })
+        .collect();
+    Json(serde_json::json!({
+        "active": state.active_model().as_str(),
+        "models": models,
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct SelectModelRequest {
+    pub model: String,
+}
+
+pub async fn select_model_handler(
+    State(state): State<AppState>,
+    Json(req): Json<SelectModelRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let Some(model) = OcrModel::from_id(&req.model) else {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("Unknown OCR model: {}", req.model),
+        ));
+    };
+    state.set_active_model(model);
+    info!("OCR: Active model set to {}", model.as_str());
+    Ok(Json(serde_json::json!({ "active": model.as_str() })))
+}
+
 pub async fn ocr_handler(
     State(state): State<AppState>,
     Query(params): Query<OcrRequest>,
-) -> Result<Json<Vec<crate::logic::OcrResult>>, (StatusCode, String)> {
-    let language = params.language.unwrap_or_default();
-    let cache_key = logic::get_cache_key(&params.url, Some(language));
+) -> Result<Json<Vec<crate::logic::OcrResult>>, Response> {
+    let language = params.language.unwrap_or_else(OcrLanguage::study_default);
+    let model = state.active_model();
+    let cache_key = logic::model_cache_key(&params.url, language, model);
     let chapter_key = params
         .base_url
         .as_ref()
-        .map(|base| logic::get_cache_key(base, Some(language)));
+        .map(|base| logic::model_cache_key(base, language, model));
     info!("OCR Handler: Incoming request for cache_key={}", cache_key);
 
     info!("OCR Handler: Checking cache...");
@@ -90,12 +141,18 @@ pub async fn ocr_handler(
         cache_key
     );
 
+    let _permit = state
+        .inference_limiter
+        .acquire()
+        .await
+        .map_err(IntoResponse::into_response)?;
     let result = logic::fetch_and_process(
         &params.url,
         params.user.clone(),
         params.pass.clone(),
         params.add_space_on_merge,
         language,
+        model,
     )
     .await;
 
@@ -128,7 +185,66 @@ pub async fn ocr_handler(
                 "OCR Handler: Processing FAILED for cache_key={}: {}",
                 cache_key, e
             );
-            Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+            Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response())
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct OcrImageParams {
+    pub user: Option<String>,
+    pub pass: Option<String>,
+    pub add_space_on_merge: Option<bool>,
+    pub language: Option<OcrLanguage>,
+}
+
+pub async fn ocr_image_handler(
+    State(state): State<AppState>,
+    Query(params): Query<OcrImageParams>,
+    body: Bytes,
+) -> Result<Json<Vec<crate::logic::OcrResult>>, Response> {
+    if body.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Empty image body").into_response());
+    }
+    let language = params.language.unwrap_or_else(OcrLanguage::study_default);
+    let model = state.active_model();
+    let cache_key = image_cache::image_cache_key(&body, language, params.add_space_on_merge, model);
+    info!(
+        "OCR Image Handler: Processing {} uploaded bytes",
+        body.len()
+    );
+
+    if let Some(data) = state.image_cache.get(&cache_key) {
+        state.requests_processed.fetch_add(1, Ordering::Relaxed);
+        return Ok(Json(data));
+    }
+    let _permit = state
+        .inference_limiter
+        .acquire()
+        .await
+        .map_err(IntoResponse::into_response)?;
+    let result = state
+        .image_cache
+        .get_or_compute(&cache_key, || {
+            logic::process_image_bytes(
+                &body,
+                params.user,
+                params.pass,
+                params.add_space_on_merge,
+                language,
+                model,
+            )
+        })
+        .await;
+
+    match result {
+        Ok(data) => {
+            state.requests_processed.fetch_add(1, Ordering::Relaxed);
+            Ok(Json(data))
+        }
+        Err(e) => {
+            warn!("OCR Image Handler: Processing FAILED: {}", e);
+            Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response())
         }
     }
 }
@@ -168,8 +284,9 @@ pub struct ChapterStatusBatchRequest {
 }
 
 async fn chapter_status(state: &AppState, req: JobRequest) -> Json<serde_json::Value> {
-    let language = req.language.unwrap_or_default();
-    let job_key = logic::get_cache_key(&req.base_url, Some(language));
+    let language = req.language.unwrap_or_else(OcrLanguage::study_default);
+    let model = state.active_model();
+    let job_key = logic::model_cache_key(&req.base_url, language, model);
     let progress = {
         state
             .active_chapter_jobs
@@ -199,7 +316,7 @@ async fn chapter_status(state: &AppState, req: JobRequest) -> Json<serde_json::V
         }
         let mut cached_keys = Vec::new();
         for page in page_list {
-            let cache_key = logic::get_cache_key(page, Some(language));
+            let cache_key = logic::model_cache_key(page, language, model);
             if state.has_cache_entry(&cache_key)
                 || state.has_cache_entry_prefix(&format!("{cache_key}?sourceId="))
                 || state.has_cache_entry_prefix(&format!("{cache_key}&sourceId="))
@@ -339,7 +456,8 @@ pub async fn preprocess_handler(
     State(state): State<AppState>,
     Json(req): Json<JobRequest>,
 ) -> Json<serde_json::Value> {
-    let language = req.language.unwrap_or_default();
+    let language = req.language.unwrap_or_else(OcrLanguage::study_default);
+    let model = state.active_model();
     let pages = match req.pages {
         Some(p) => p,
         None => return Json(serde_json::json!({ "error": "No pages provided" })),
@@ -350,7 +468,7 @@ pub async fn preprocess_handler(
             .active_chapter_jobs
             .read()
             .expect("lock poisoned")
-            .contains_key(&logic::get_cache_key(&req.base_url, Some(language)))
+            .contains_key(&logic::model_cache_key(&req.base_url, language, model))
     };
 
     if is_processing {
@@ -368,6 +486,7 @@ pub async fn preprocess_handler(
             req.context,
             req.add_space_on_merge,
             language,
+            model,
         )
         .await;
     });
@@ -386,8 +505,8 @@ pub async fn delete_chapter_handler(
     State(state): State<AppState>,
     Json(req): Json<DeleteChapterRequest>,
 ) -> Json<serde_json::Value> {
-    let language = req.language.unwrap_or_default();
-    let chapter_key = logic::get_cache_key(&req.base_url, Some(language));
+    let language = req.language.unwrap_or_else(OcrLanguage::study_default);
+    let chapter_key = logic::model_cache_key(&req.base_url, language, state.active_model());
     let delete_data = req.delete_data.unwrap_or(true);
 
     // If a job is currently tracked, drop the progress entry.
@@ -0,0 +1,175 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    future::Future,
+    sync::Mutex,
+};
+
+use sha2::{Digest, Sha256};
+
+use crate::{language::OcrLanguage, logic::OcrResult, model::OcrModel};
+
+/// Default number of OCR results kept in memory for uploaded images.
+pub const DEFAULT_IMAGE_CACHE_CAPACITY: usize = 64;
+
+/// Capacity for the uploaded-image cache, overridable via `MANATAN_OCR_IMAGE_CACHE_SIZE`.
+/// `0` disables caching.
+pub fn image_cache_capacity() -> usize {
+    std::env::var("MANATAN_OCR_IMAGE_CACHE_SIZE")
+        .ok()
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .unwrap_or(DEFAULT_IMAGE_CACHE_CAPACITY)
+}
+
+/// Content-addressed cache key: the same bytes OCR'd with the same options always produce the
+/// same result, so entries never need invalidation.
+pub fn image_cache_key(
+    image_bytes: &[u8],
+    language: OcrLanguage,
+    add_space_on_merge: Option<bool>,
+    model: OcrModel,
+) -> String {
+    let digest = format!("{:x}", Sha256::digest(image_bytes));
+    format!(
+        "{digest}:{language:?}:{add_space_on_merge:?}:{}",
+        model.as_str()
+    )
+}
+
+/// Bounded least-recently-used cache of OCR results keyed by [`image_cache_key`].
+pub struct ImageOcrCache {
+    capacity: usize,
+    inner: Mutex<CacheInner>,
+}
+
+#[derive(Default)]
+struct CacheInner {
+    entries: HashMap<String, Vec<OcrResult>>,
+    order: VecDeque<String>,
+}
+
+impl ImageOcrCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: Mutex::new(CacheInner::default()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.lock().expect("lock").entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, key: &str) -> Option<Vec<OcrResult>> {
+        let mut inner = self.inner.lock().expect("lock");
+        let data = inner.entries.get(key).cloned()?;
+        inner.order.retain(|k| k != key);
+        inner.order.push_back(key.to_string());
+        Some(data)
+    }
+
+    pub fn insert(&self, key: String, data: Vec<OcrResult>) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut inner = self.inner.lock().expect("lock");
+        inner.order.retain(|k| k != &key);
+        inner.order.push_back(key.clone());
+        inner.entries.insert(key, data);
+        while inner.entries.len() > self.capacity {
+            let Some(oldest) = inner.order.pop_front() else {
+                break;
+            };
+            inner.entries.remove(&oldest);
+        }
+    }
+
+    /// Returns the cached result for `key`, or runs `compute` and caches a successful result.
+    pub async fn get_or_compute<F, Fut>(
+        &self,
+        key: &str,
+        compute: F,
+    ) -> anyhow::Result<Vec<OcrResult>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = anyhow::Result<Vec<OcrResult>>>,
+    {
+        if let Some(data) = self.get(key) {
+            return Ok(data);
+        }
+        let data = compute().await?;
+        self.insert(key.to_string(), data.clone());
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::logic::BoundingBox;
+
+    fn sample_result(text: &str) -> Vec<OcrResult> {
+        vec![OcrResult {
+            text: text.to_string(),
+            is_merged: Some(false),
+            forced_orientation: None,
+            tight_bounding_box: BoundingBox {
+                x: 0.0,
+                y: 0.0,
+                width: 1.0,
+                height: 1.0,
+                rotation: None,
+            },
+        }]
+    }
+
+    #[tokio::test]
+    async fn same_image_bytes_hit_the_cache() {
+        let cache = ImageOcrCache::new(4);
+        let calls = AtomicUsize::new(0);
+        let image = b"fake image bytes";
+
+        for _ in 0..2 {
+            let key = image_cache_key(image, OcrLanguage::Japanese, None, OcrModel::default());
+            let data = cache
+                .get_or_compute(&key, || async {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    Ok(sample_result("こんにちは"))
+                })
+                .await
+                .expect("ocr");
+            assert_eq!(data[0].text, "こんにちは");
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // A different language is a different key.
+        let key = image_cache_key(image, OcrLanguage::English, None, OcrModel::default());
+        cache
+            .get_or_compute(&key, || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Ok(sample_result("hello"))
+            })
+            .await
+            .expect("ocr");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn evicts_least_recently_used_entry() {
+        let cache = ImageOcrCache::new(2);
+        cache.insert("a".to_string(), sample_result("a"));
+        cache.insert("b".to_string(), sample_result("b"));
+        assert!(cache.get("a").is_some());
+        cache.insert("c".to_string(), sample_result("c"));
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get("a").is_some());
+        assert!(cache.get("b").is_none());
+        assert!(cache.get("c").is_some());
+    }
+}
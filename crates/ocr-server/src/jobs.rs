@@ -7,6 +7,7 @@ use futures::StreamExt;
 
 use crate::{
     language::OcrLanguage,
+    model::OcrModel,
     state::{AppState, JobProgress},
 };
 
@@ -20,9 +21,10 @@ pub async fn run_chapter_job(
     context: String,
     add_space_on_merge: Option<bool>,
     language: OcrLanguage,
+    model: OcrModel,
 ) {
     let total = pages.len();
-    let job_id = crate::logic::get_cache_key(&base_url, Some(language));
+    let job_id = crate::logic::model_cache_key(&base_url, language, model);
 
     {
         state
@@ -55,7 +57,7 @@ pub async fn run_chapter_job(
             let page_id = url.split('/').next_back().unwrap_or("unknown").to_string();
 
             async move {
-                let cache_key = crate::logic::get_cache_key(&url, Some(language));
+                let cache_key = crate::logic::model_cache_key(&url, language, model);
                 let exists = state.has_cache_entry(&cache_key);
                 if exists {
                     state.insert_chapter_cache(&job_id, &cache_key);
@@ -71,6 +73,7 @@ pub async fn run_chapter_job(
                         pass,
                         add_space_on_merge,
                         language,
+                        model,
                     )
                     .await
                     {
@@ -114,4 +114,35 @@ impl OcrLanguage {
     pub fn is_japanese(&self) -> bool {
         matches!(self, OcrLanguage::Japanese)
     }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        serde_json::from_value(serde_json::Value::String(name.trim().to_lowercase())).ok()
+    }
+
+    /// Language used when a request doesn't name one: `MANATAN_STUDY_LANGUAGE` if set,
+    /// otherwise Japanese.
+    pub fn study_default() -> Self {
+        std::env::var("MANATAN_STUDY_LANGUAGE")
+            .ok()
+            .and_then(|value| Self::from_name(&value))
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OcrLanguage;
+
+    #[test]
+    fn parses_study_language_names() {
+        assert_eq!(
+            OcrLanguage::from_name(" Korean "),
+            Some(OcrLanguage::Korean)
+        );
+        assert_eq!(
+            OcrLanguage::from_name("chinese"),
+            Some(OcrLanguage::Chinese)
+        );
+        assert_eq!(OcrLanguage::from_name("klingon"), None);
+    }
 }
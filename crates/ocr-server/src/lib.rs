@@ -1,11 +1,14 @@
 pub mod handlers;
+pub mod image_cache;
 pub mod jobs;
 pub mod language;
+pub mod limiter;
 pub mod logic;
 pub mod merge;
+pub mod model;
 pub mod state;
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use axum::{
     Router,
@@ -14,16 +17,47 @@ use axum::{
 };
 use state::AppState;
 
-/// Creates the OCR Router.
-pub fn create_router(cache_dir: PathBuf) -> Router {
-    let state = AppState::new(cache_dir);
+/// Default cap for images posted to `/ocr-image`; page scans are far smaller than this.
+const DEFAULT_MAX_IMAGE_BYTES: usize = 32 * 1024 * 1024;
+
+/// Upload limit for `/ocr-image`, overridable via `MANATAN_OCR_MAX_IMAGE_BYTES`.
+fn max_image_bytes() -> usize {
+    std::env::var("MANATAN_OCR_MAX_IMAGE_BYTES")
+        .ok()
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .filter(|value| *value > 0)
+        .unwrap_or(DEFAULT_MAX_IMAGE_BYTES)
+}
+
+/// Subdirectory of the shared data dir holding the OCR cache.
+pub const STORAGE_DIR_NAME: &str = "ocr";
+
+/// Cache files older builds wrote straight into the shared data dir.
+const LEGACY_ROOT_ENTRIES: [&str; 3] = ["ocr-cache.db", "ocr-cache.db-journal", "ocr-cache.json"];
+
+/// Returns `data_dir/ocr`, moving cache files left in the shared root by older builds into it.
+pub fn storage_dir(data_dir: &Path) -> PathBuf {
+    manatan_storage::namespaced_dir(data_dir, STORAGE_DIR_NAME, &LEGACY_ROOT_ENTRIES)
+}
+
+/// Creates the OCR Router. `data_dir` is the shared data dir; the cache lives in its `ocr/`
+/// subdirectory.
+pub fn create_router(data_dir: PathBuf) -> Router {
+    let state = AppState::new(storage_dir(&data_dir));
 
     // Spawn the job worker if you want strict concurrency,
     // or we just spawn tasks per request (handled in handlers).
 
     Router::new()
         .route("/", get(handlers::status_handler))
+        .route("/version", get(handlers::version_handler))
         .route("/ocr", get(handlers::ocr_handler))
+        .route("/models", get(handlers::models_handler))
+        .route("/model", post(handlers::select_model_handler))
+        .route(
+            "/ocr-image",
+            post(handlers::ocr_image_handler).layer(DefaultBodyLimit::max(max_image_bytes())),
+        )
         .route(
             "/is-chapter-preprocessed",
             get(handlers::is_chapter_preprocessed_get_handler)
@@ -0,0 +1,114 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use axum::{
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Default number of interactive OCR inferences allowed to run at once.
+pub const DEFAULT_MAX_CONCURRENCY: usize = 2;
+
+/// Default number of requests allowed to wait for a free slot before new ones are turned away.
+pub const DEFAULT_MAX_QUEUED: usize = 8;
+
+/// `Retry-After` sent with the `503` returned when the queue is full.
+pub const RETRY_AFTER_SECS: u64 = 2;
+
+/// Concurrent inference cap, overridable via `MANATAN_OCR_MAX_CONCURRENCY`.
+pub fn max_concurrency() -> usize {
+    std::env::var("MANATAN_OCR_MAX_CONCURRENCY")
+        .ok()
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .filter(|value| *value > 0)
+        .unwrap_or(DEFAULT_MAX_CONCURRENCY)
+}
+
+/// Queue length, overridable via `MANATAN_OCR_MAX_QUEUE`. `0` rejects anything over the cap.
+pub fn max_queued() -> usize {
+    std::env::var("MANATAN_OCR_MAX_QUEUE")
+        .ok()
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .unwrap_or(DEFAULT_MAX_QUEUED)
+}
+
+/// Returned by [`InferenceLimiter::acquire`] when every slot is busy and the queue is full.
+#[derive(Debug)]
+pub struct QueueFull;
+
+impl IntoResponse for QueueFull {
+    fn into_response(self) -> Response {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            [(header::RETRY_AFTER, RETRY_AFTER_SECS.to_string())],
+            "OCR is busy, retry shortly",
+        )
+            .into_response()
+    }
+}
+
+/// Bounds how many OCR inferences run at once so rapid taps on a phone queue up instead of
+/// running side by side. Chapter preprocessing has its own limit and does not go through here.
+pub struct InferenceLimiter {
+    semaphore: Arc<Semaphore>,
+    max_concurrency: usize,
+    max_queued: usize,
+    queued: AtomicUsize,
+}
+
+/// Releases a queue slot even if the waiting request is dropped.
+struct QueuedGuard<'a>(&'a AtomicUsize);
+
+impl Drop for QueuedGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl InferenceLimiter {
+    pub fn new(max_concurrency: usize, max_queued: usize) -> Self {
+        let max_concurrency = max_concurrency.max(1);
+        Self {
+            semaphore: Arc::new(Semaphore::new(max_concurrency)),
+            max_concurrency,
+            max_queued,
+            queued: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(max_concurrency(), max_queued())
+    }
+
+    pub fn max_concurrency(&self) -> usize {
+        self.max_concurrency
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.max_concurrency - self.semaphore.available_permits()
+    }
+
+    pub fn queued(&self) -> usize {
+        self.queued.load(Ordering::SeqCst)
+    }
+
+    /// Waits for a free slot. The returned permit frees the slot when dropped.
+    pub async fn acquire(&self) -> Result<OwnedSemaphorePermit, QueueFull> {
+        if let Ok(permit) = self.semaphore.clone().try_acquire_owned() {
+            return Ok(permit);
+        }
+        if self.queued.fetch_add(1, Ordering::SeqCst) >= self.max_queued {
+            self.queued.fetch_sub(1, Ordering::SeqCst);
+            return Err(QueueFull);
+        }
+        let _queued = QueuedGuard(&self.queued);
+        self.semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|_| QueueFull)
+    }
+}
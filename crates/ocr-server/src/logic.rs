@@ -9,6 +9,7 @@ use serde::{Deserialize, Serialize};
 use crate::{
     language::OcrLanguage,
     merge::{self, MergeConfig},
+    model::OcrModel,
 };
 
 // --- REST Structs ---
@@ -217,6 +218,17 @@ pub fn get_cache_key(url: &str, language: Option<OcrLanguage>) -> String {
     }
 }
 
+/// Cache key for `url` OCR'd with `model`. The default model keeps the unprefixed key so entries
+/// cached before model selection existed still hit.
+pub fn model_cache_key(url: &str, language: OcrLanguage, model: OcrModel) -> String {
+    let key = get_cache_key(url, Some(language));
+    if model == OcrModel::default() {
+        key
+    } else {
+        format!("model/{}/{key}", model.as_str())
+    }
+}
+
 fn post_process_text(text: String, language: OcrLanguage) -> String {
     if language.prefers_no_space() {
         text.replace(char::is_whitespace, "")
@@ -289,6 +301,7 @@ pub async fn fetch_and_process(
     pass: Option<String>,
     add_space_on_merge: Option<bool>,
     language: OcrLanguage,
+    model: OcrModel,
 ) -> anyhow::Result<Vec<OcrResult>> {
     let mut last_error = anyhow!("Unknown error");
 
@@ -299,6 +312,7 @@ pub async fn fetch_and_process(
             pass.clone(),
             add_space_on_merge,
             language,
+            model,
         )
         .await
         {
@@ -519,6 +533,7 @@ async fn fetch_and_process_internal(
     pass: Option<String>,
     add_space_on_merge: Option<bool>,
     language: OcrLanguage,
+    model: OcrModel,
 ) -> anyhow::Result<Vec<OcrResult>> {
     // 0. Force URL to Localhost
     let target_url = match reqwest::Url::parse(url) {
@@ -544,8 +559,30 @@ async fn fetch_and_process_internal(
         .map_err(|err| anyhow!("Failed error_for_status (URL: {target_url}): {err:?}"))?;
     let image_bytes = response.bytes().await?.to_vec();
 
+    process_image_bytes(
+        &image_bytes,
+        user,
+        pass,
+        add_space_on_merge,
+        language,
+        model,
+    )
+    .await
+}
+
+/// Runs OCR on already-loaded image bytes and returns merged, normalized results.
+pub async fn process_image_bytes(
+    image_bytes: &[u8],
+    user: Option<String>,
+    pass: Option<String>,
+    add_space_on_merge: Option<bool>,
+    language: OcrLanguage,
+    model: OcrModel,
+) -> anyhow::Result<Vec<OcrResult>> {
     // 2. Decode & OCR (Wrapped) - now passes user/pass for proxy settings
-    let raw_chunks = get_raw_ocr_data(&image_bytes, user, pass, language).await?;
+    let raw_chunks = match model {
+        OcrModel::ChromeLens => get_raw_ocr_data(image_bytes, user, pass, language).await?,
+    };
 
     // 3. Merge & Normalize
     let mut final_results = Vec::new();
@@ -578,3 +615,21 @@ async fn fetch_and_process_internal(
 
     Ok(final_results)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_model_keeps_legacy_cache_key() {
+        let url = "http://127.0.0.1:4568/api/v1/manga/1/chapter/2/page/3?sourceId=9";
+        assert_eq!(
+            model_cache_key(url, OcrLanguage::Japanese, OcrModel::default()),
+            get_cache_key(url, Some(OcrLanguage::Japanese))
+        );
+        assert_eq!(
+            model_cache_key(url, OcrLanguage::Japanese, OcrModel::ChromeLens),
+            "lang/japanese/api/v1/manga/1/chapter/2/page/3"
+        );
+    }
+}
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+/// OCR engines the server can run. Only Google Lens is bundled today; the enum keeps the
+/// selection API stable as more engines are added.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum OcrModel {
+    #[default]
+    ChromeLens,
+}
+
+impl OcrModel {
+    pub const ALL: &'static [OcrModel] = &[OcrModel::ChromeLens];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OcrModel::ChromeLens => "chrome-lens",
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            OcrModel::ChromeLens => "Google Lens",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|model| model.as_str() == id)
+    }
+}
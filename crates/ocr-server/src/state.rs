@@ -11,7 +11,12 @@ use rusqlite::{OptionalExtension, params};
 use serde::{Deserialize, Serialize};
 use tracing::{info, warn};
 
-use crate::logic::OcrResult;
+use crate::{
+    image_cache::{ImageOcrCache, image_cache_capacity},
+    limiter::InferenceLimiter,
+    logic::OcrResult,
+    model::OcrModel,
+};
 
 #[derive(Clone, Copy, Serialize, Debug)]
 pub struct JobProgress {
@@ -26,6 +31,9 @@ pub struct AppState {
     pub active_jobs: Arc<AtomicUsize>,
     pub requests_processed: Arc<AtomicUsize>,
     pub active_chapter_jobs: Arc<RwLock<HashMap<String, JobProgress>>>,
+    pub image_cache: Arc<ImageOcrCache>,
+    pub active_model: Arc<RwLock<OcrModel>>,
+    pub inference_limiter: Arc<InferenceLimiter>,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -112,11 +120,22 @@ impl AppState {
             active_jobs: Arc::new(AtomicUsize::new(0)),
             requests_processed: Arc::new(AtomicUsize::new(0)),
             active_chapter_jobs: Arc::new(RwLock::new(HashMap::new())),
+            image_cache: Arc::new(ImageOcrCache::new(image_cache_capacity())),
+            active_model: Arc::new(RwLock::new(OcrModel::default())),
+            inference_limiter: Arc::new(InferenceLimiter::from_env()),
         }
     }
 }
 
 impl AppState {
+    pub fn active_model(&self) -> OcrModel {
+        *self.active_model.read().expect("lock")
+    }
+
+    pub fn set_active_model(&self, model: OcrModel) {
+        *self.active_model.write().expect("lock") = model;
+    }
+
     pub fn cache_len(&self) -> usize {
         let Ok(conn) = self.pool.get() else {
             warn!("Failed to get DB connection for cache_len");
@@ -0,0 +1,50 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use axum::{http::StatusCode, response::IntoResponse};
+use manatan_ocr_server::limiter::InferenceLimiter;
+
+#[tokio::test]
+async fn limiter_never_exceeds_the_cap() {
+    let limiter = Arc::new(InferenceLimiter::new(2, 8));
+    let running = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+
+    let tasks: Vec<_> = (0..6)
+        .map(|_| {
+            let limiter = limiter.clone();
+            let running = running.clone();
+            let peak = peak.clone();
+            tokio::spawn(async move {
+                let _permit = limiter.acquire().await.expect("queue has room");
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+            })
+        })
+        .collect();
+    for task in tasks {
+        task.await.expect("task");
+    }
+
+    assert_eq!(peak.load(Ordering::SeqCst), 2);
+    assert_eq!(limiter.in_flight(), 0);
+    assert_eq!(limiter.queued(), 0);
+}
+
+#[tokio::test]
+async fn full_queue_is_rejected_with_retry_after() {
+    let limiter = InferenceLimiter::new(1, 0);
+    let _held = limiter.acquire().await.expect("first slot");
+
+    let rejected = limiter.acquire().await.expect_err("queue is full");
+    let response = rejected.into_response();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert!(response.headers().contains_key("retry-after"));
+}
@@ -0,0 +1,37 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn oversized_image_upload_is_rejected() {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("time")
+        .as_nanos();
+    let dir = std::env::temp_dir().join(format!(
+        "manatan-ocr-image-limit-test-{}-{nanos}",
+        std::process::id()
+    ));
+
+    // One byte over the default 32MB cap.
+    let body = vec![0u8; 32 * 1024 * 1024 + 1];
+    let response = manatan_ocr_server::create_router(dir.clone())
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/ocr-image")
+                .header(header::CONTENT_TYPE, "application/octet-stream")
+                .header(header::CONTENT_LENGTH, body.len())
+                .body(Body::from(body))
+                .expect("request"),
+        )
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+    let _ = std::fs::remove_dir_all(dir);
+}
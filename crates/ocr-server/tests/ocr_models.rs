@@ -0,0 +1,66 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::{
+    Router,
+    body::{Body, to_bytes},
+    http::{Request, StatusCode, header},
+};
+use serde_json::Value;
+use tower::ServiceExt;
+
+async fn send(router: &Router, request: Request<Body>) -> (StatusCode, Option<Value>) {
+    let response = router.clone().oneshot(request).await.expect("response");
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    (status, serde_json::from_slice(&body).ok())
+}
+
+fn select(model: &str) -> Request<Body> {
+    Request::builder()
+        .method("POST")
+        .uri("/model")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(format!(r#"{{"model":"{model}"}}"#)))
+        .expect("request")
+}
+
+fn get(uri: &str) -> Request<Body> {
+    Request::builder()
+        .uri(uri)
+        .body(Body::empty())
+        .expect("request")
+}
+
+#[tokio::test]
+async fn lists_and_selects_ocr_models() {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("time")
+        .as_nanos();
+    let dir = std::env::temp_dir().join(format!(
+        "manatan-ocr-models-test-{}-{nanos}",
+        std::process::id()
+    ));
+    let router = manatan_ocr_server::create_router(dir.clone());
+
+    let (status, json) = send(&router, get("/models")).await;
+    assert_eq!(status, StatusCode::OK);
+    let json = json.expect("json");
+    assert_eq!(json["active"], "chrome-lens");
+    assert_eq!(json["models"][0]["id"], "chrome-lens");
+
+    let (status, _) = send(&router, select("does-not-exist")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, json) = send(&router, select("chrome-lens")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json.expect("json")["active"], "chrome-lens");
+
+    let (status, json) = send(&router, get("/")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json.expect("json")["model"], "chrome-lens");
+
+    let _ = std::fs::remove_dir_all(dir);
+}
@@ -0,0 +1,15 @@
+use manatan_ocr_server::STORAGE_DIR_NAME;
+
+#[test]
+fn cache_is_written_under_its_own_subdir() {
+    let temp = tempfile::tempdir().expect("temp dir");
+    let dir = temp.path();
+    std::fs::write(dir.join("ocr-cache.json"), b"{}").expect("legacy cache");
+
+    let _router = manatan_ocr_server::create_router(dir.to_path_buf());
+
+    let storage = dir.join(STORAGE_DIR_NAME);
+    assert!(storage.join("ocr-cache.db").exists());
+    assert!(!dir.join("ocr-cache.db").exists());
+    assert!(!dir.join("ocr-cache.json").exists());
+}
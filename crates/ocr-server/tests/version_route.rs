@@ -0,0 +1,28 @@
+use axum::{
+    body::{Body, to_bytes},
+    http::{Request, StatusCode},
+};
+use serde_json::Value;
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn version_route_reports_crate_version() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let response = manatan_ocr_server::create_router(dir.path().to_path_buf())
+        .oneshot(
+            Request::builder()
+                .uri("/version")
+                .body(Body::empty())
+                .expect("request"),
+        )
+        .await
+        .expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    let json: Value = serde_json::from_slice(&body).expect("json");
+    assert_eq!(json["name"], "manatan-ocr-server");
+    assert_eq!(json["version"], env!("CARGO_PKG_VERSION"));
+}
@@ -0,0 +1,66 @@
+//! Layout of the shared data dir: each server keeps everything it writes under its own
+//! subdirectory, e.g. `yomitan/` or `sync/`.
+
+use std::path::{Path, PathBuf};
+
+use tracing::{info, warn};
+
+/// Returns `data_dir/name`, creating it if needed. Entries listed in `legacy` that older builds
+/// wrote straight into `data_dir` are moved in, unless the subdirectory already has its own copy.
+/// Failures are logged rather than returned; the server then reports them when it opens the files.
+pub fn namespaced_dir(data_dir: &Path, name: &str, legacy: &[&str]) -> PathBuf {
+    let dir = data_dir.join(name);
+    if let Err(err) = std::fs::create_dir_all(&dir) {
+        warn!("Failed to create {}: {err}", dir.display());
+        return dir;
+    }
+    for entry in legacy {
+        let old = data_dir.join(entry);
+        let target = dir.join(entry);
+        if !old.exists() || target.exists() {
+            continue;
+        }
+        match std::fs::rename(&old, &target) {
+            Ok(()) => info!("📂 Moved {} into {}", entry, dir.display()),
+            Err(err) => warn!(
+                "Failed to move {} into {}: {err}",
+                old.display(),
+                dir.display()
+            ),
+        }
+    }
+    dir
+}
+
+#[cfg(test)]
+mod tests {
+    use super::namespaced_dir;
+
+    #[test]
+    fn creates_the_subdir() {
+        let temp = tempfile::tempdir().expect("temp dir");
+
+        let dir = namespaced_dir(temp.path(), "audio", &[]);
+
+        assert_eq!(dir, temp.path().join("audio"));
+        assert!(dir.is_dir());
+    }
+
+    #[test]
+    fn moves_legacy_entries_without_clobbering_newer_ones() {
+        let temp = tempfile::tempdir().expect("temp dir");
+        let root = temp.path();
+        std::fs::write(root.join("cache.db"), b"old").expect("legacy file");
+        std::fs::create_dir_all(root.join("media").join("Dict")).expect("legacy dir");
+        std::fs::create_dir_all(root.join("ocr")).expect("subdir");
+        std::fs::write(root.join("ocr").join("media"), b"new").expect("newer copy");
+
+        let dir = namespaced_dir(root, "ocr", &["cache.db", "media", "missing.json"]);
+
+        assert_eq!(std::fs::read(dir.join("cache.db")).expect("moved"), b"old");
+        assert!(!root.join("cache.db").exists());
+        assert_eq!(std::fs::read(dir.join("media")).expect("kept"), b"new");
+        assert!(root.join("media").join("Dict").is_dir());
+        assert!(!dir.join("missing.json").exists());
+    }
+}
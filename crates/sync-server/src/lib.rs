@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use axum::{Router, extract::DefaultBodyLimit};
 use tower_http::cors::{Any, CorsLayer};
@@ -14,6 +14,16 @@ pub use error::SyncError;
 pub use state::SyncState;
 pub use types::*;
 
+/// Subdirectory of the shared data dir holding the sync database and OAuth tokens.
+pub const STORAGE_DIR_NAME: &str = "sync";
+
+/// Returns `data_dir/sync`, creating it if needed.
+pub fn storage_dir(data_dir: &Path) -> PathBuf {
+    manatan_storage::namespaced_dir(data_dir, STORAGE_DIR_NAME, &[])
+}
+
+/// Creates the sync Router. `data_dir` is the shared data dir; everything lives in its `sync/`
+/// subdirectory.
 pub fn create_router(data_dir: PathBuf) -> Router {
     let state = SyncState::new(data_dir);
 
@@ -27,3 +37,51 @@ pub fn create_router(data_dir: PathBuf) -> Router {
         .layer(DefaultBodyLimit::disable())
         .with_state(state)
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{Body, to_bytes},
+        http::{Request, StatusCode},
+    };
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn version_route_reports_crate_version() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let response = create_router(dir.path().to_path_buf())
+            .oneshot(
+                Request::builder()
+                    .uri("/version")
+                    .body(Body::empty())
+                    .expect("request"),
+            )
+            .await
+            .expect("response");
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let json: Value = serde_json::from_slice(&body).expect("json");
+        assert_eq!(json["name"], "manatan-sync-server");
+        assert_eq!(json["version"], env!("CARGO_PKG_VERSION"));
+    }
+
+    #[test]
+    fn storage_is_namespaced_under_sync() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let state = SyncState::new(dir.path().to_path_buf());
+
+        assert_eq!(state.data_dir, dir.path().join(STORAGE_DIR_NAME));
+        let root_entries: Vec<_> = std::fs::read_dir(dir.path())
+            .expect("read data dir")
+            .map(|entry| entry.expect("entry").file_name())
+            .collect();
+        assert_eq!(root_entries, [STORAGE_DIR_NAME]);
+        assert!(state.data_dir.join("sync.db").exists());
+    }
+}
@@ -2,19 +2,27 @@ use std::collections::HashMap;
 
 use tracing::debug;
 
-use crate::types::{ConflictInfo, LNMetadata, LNProgress, LnCategory, SyncPayload};
+use crate::types::{
+    ConflictInfo, LNChapterProgress, LNMetadata, LNProgress, LnCategory, ProgressGranularity,
+    SyncPayload,
+};
 
 /// Merge two sync payloads, returning the merged result
 pub fn merge_payloads(
     local: SyncPayload,
     remote: SyncPayload,
     local_device_id: &str,
+    granularity: ProgressGranularity,
 ) -> (SyncPayload, Vec<ConflictInfo>) {
     let mut conflicts = Vec::new();
 
     // Merge progress
-    let (merged_progress, progress_conflicts) =
-        merge_progress_maps(local.ln_progress, remote.ln_progress, local_device_id);
+    let (merged_progress, progress_conflicts) = merge_progress_maps(
+        local.ln_progress,
+        remote.ln_progress,
+        local_device_id,
+        granularity,
+    );
     conflicts.extend(progress_conflicts);
 
     // Merge metadata
@@ -82,6 +90,7 @@ fn merge_progress_maps(
     local: HashMap<String, LNProgress>,
     remote: HashMap<String, LNProgress>,
     local_device_id: &str,
+    granularity: ProgressGranularity,
 ) -> (HashMap<String, LNProgress>, Vec<ConflictInfo>) {
     let mut merged = HashMap::new();
     let mut conflicts = Vec::new();
@@ -94,7 +103,7 @@ fn merge_progress_maps(
         let local_progress = local.get(&book_id);
         let remote_progress = remote.get(&book_id);
 
-        let chosen = match (local_progress, remote_progress) {
+        let mut chosen = match (local_progress, remote_progress) {
             (Some(l), None) => {
                 debug!("Progress for {}: local only", book_id);
                 l.clone()
@@ -164,12 +173,42 @@ fn merge_progress_maps(
             (None, None) => unreachable!(),
         };
 
+        if granularity == ProgressGranularity::Chapter
+            && let (Some(l), Some(r)) = (local_progress, remote_progress)
+        {
+            chosen.chapters = merge_chapter_progress(&l.chapters, &r.chapters);
+        }
+
         merged.insert(book_id, chosen);
     }
 
     (merged, conflicts)
 }
 
+/// Union of both sides' per-chapter positions. For a chapter read on both devices the most
+/// recently read one wins, falling back to the further one.
+fn merge_chapter_progress(
+    local: &HashMap<i32, LNChapterProgress>,
+    remote: &HashMap<i32, LNChapterProgress>,
+) -> HashMap<i32, LNChapterProgress> {
+    let mut merged = remote.clone();
+    for (chapter, l) in local {
+        let keep_local = match merged.get(chapter) {
+            Some(r) => match (l.last_read, r.last_read) {
+                (Some(lt), Some(rt)) if lt != rt => lt > rt,
+                (Some(_), None) => true,
+                (None, Some(_)) => false,
+                _ => l.chapter_progress >= r.chapter_progress,
+            },
+            None => true,
+        };
+        if keep_local {
+            merged.insert(*chapter, l.clone());
+        }
+    }
+    merged
+}
+
 fn merge_metadata_maps(
     local: HashMap<String, LNMetadata>,
     remote: HashMap<String, LNMetadata>,
@@ -223,3 +262,81 @@ fn merge_simple_maps<V: Clone>(
     }
     merged
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(device_id: &str, total_progress: f64) -> LNProgress {
+        LNProgress {
+            total_progress,
+            device_id: Some(device_id.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn superseded_local_progress_is_reported_as_conflict() {
+        let mut local = SyncPayload::new("phone".to_string());
+        local
+            .ln_progress
+            .insert("book-1".to_string(), progress("phone", 0.25));
+        let mut remote = SyncPayload::new("tablet".to_string());
+        remote
+            .ln_progress
+            .insert("book-1".to_string(), progress("tablet", 0.5));
+
+        let (merged, conflicts) =
+            merge_payloads(local, remote, "phone", ProgressGranularity::Chapter);
+
+        assert_eq!(merged.ln_progress["book-1"].total_progress, 0.5);
+        assert_eq!(conflicts.len(), 1);
+        let conflict = &conflicts[0];
+        assert_eq!(conflict.book_id, "book-1");
+        assert_eq!(conflict.field, "progress");
+        assert_eq!(conflict.local_value, "25.0%");
+        assert_eq!(conflict.remote_value, "50.0%");
+        assert_eq!(conflict.resolution, "remote (further)");
+    }
+
+    fn chapter(chapter_progress: f64, last_read: i64) -> LNChapterProgress {
+        LNChapterProgress {
+            chapter_progress,
+            last_read: Some(last_read),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn chapters_read_on_different_devices_are_both_kept() {
+        let mut phone = progress("phone", 0.4);
+        phone.chapters.insert(5, chapter(0.6, 1_000));
+        phone.chapters.insert(3, chapter(1.0, 500));
+        let mut desktop = progress("desktop", 0.5);
+        desktop.chapters.insert(6, chapter(0.3, 2_000));
+        desktop.chapters.insert(3, chapter(0.2, 100));
+
+        let mut local = SyncPayload::new("phone".to_string());
+        local.ln_progress.insert("book-1".to_string(), phone);
+        let mut remote = SyncPayload::new("desktop".to_string());
+        remote.ln_progress.insert("book-1".to_string(), desktop);
+
+        let (merged, _) = merge_payloads(
+            local.clone(),
+            remote.clone(),
+            "phone",
+            ProgressGranularity::Chapter,
+        );
+        let book = &merged.ln_progress["book-1"];
+        assert_eq!(book.total_progress, 0.5);
+        assert_eq!(book.chapters.len(), 3);
+        assert_eq!(book.chapters[&5], chapter(0.6, 1_000));
+        assert_eq!(book.chapters[&6], chapter(0.3, 2_000));
+        assert_eq!(book.chapters[&3], chapter(1.0, 500));
+
+        let (merged, _) = merge_payloads(local, remote, "phone", ProgressGranularity::Book);
+        let book = &merged.ln_progress["book-1"];
+        assert!(!book.chapters.contains_key(&5));
+        assert_eq!(book.chapters[&3], chapter(0.2, 100));
+    }
+}
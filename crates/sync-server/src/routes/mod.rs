@@ -1,4 +1,5 @@
-use axum::Router;
+use axum::{Json, Router, routing::get};
+use serde_json::{Value, json};
 
 use crate::state::SyncState;
 
@@ -11,4 +12,12 @@ pub fn router() -> Router<SyncState> {
         .nest("/auth", auth::router())
         .nest("/config", config::router())
         .merge(sync::router())
+        .route("/version", get(version_handler))
+}
+
+async fn version_handler() -> Json<Value> {
+    Json(json!({
+        "name": env!("CARGO_PKG_NAME"),
+        "version": env!("CARGO_PKG_VERSION"),
+    }))
 }
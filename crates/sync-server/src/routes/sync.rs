@@ -115,7 +115,9 @@ async fn merge_handler(
                 device_id, remote_device_id
             );
             info!("[MERGE] Merging payloads...");
-            let (merged, conflicts) = merge_payloads(local_payload, remote_payload, &device_id);
+            let granularity = state.get_sync_config().progress_granularity;
+            let (merged, conflicts) =
+                merge_payloads(local_payload, remote_payload, &device_id, granularity);
 
             let merged_progress = merged.ln_progress.len();
             let merged_metadata = merged.ln_metadata.len();
@@ -213,6 +215,8 @@ pub struct PushRequest {
     pub etag: Option<String>,
 }
 
+/// Push replaces the remote payload only when `etag` still matches (409 otherwise), so it never
+/// supersedes client progress and carries no conflict list; `/merge` reports those.
 #[derive(serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PushResponse {
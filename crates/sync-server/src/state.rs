@@ -24,8 +24,7 @@ pub struct SyncState {
 
 impl SyncState {
     pub fn new(data_dir: PathBuf) -> Self {
-        let sync_dir = data_dir.join("sync");
-        std::fs::create_dir_all(&sync_dir).expect("Failed to create sync directory");
+        let sync_dir = crate::storage_dir(&data_dir);
 
         let db_path = sync_dir.join("sync.db");
         let db = sled::open(db_path).expect("Failed to open sync database");
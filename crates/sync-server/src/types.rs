@@ -72,6 +72,28 @@ pub struct LNProgress {
     #[serde(default)]
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub highlights: Vec<LNHighlight>,
+
+    /// Last position inside each chapter read so far (chapter index → position)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub chapters: HashMap<i32, LNChapterProgress>,
+}
+
+/// Position within a single chapter, kept so progress in different chapters survives a merge
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LNChapterProgress {
+    /// Character offset within the chapter
+    pub chapter_char_offset: i32,
+
+    /// Progress within the chapter (0.0 - 1.0)
+    pub chapter_progress: f64,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_number: Option<i32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_read: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -513,7 +535,8 @@ pub struct MergeResponse {
     #[serde(default)]
     pub files_to_download: Vec<String>,
 
-    /// Any conflicts that occurred (informational)
+    /// Entries where one side's value was superseded during the merge, so the UI can tell
+    /// the user their local progress was overridden (informational)
     #[serde(default)]
     pub conflicts: Vec<ConflictInfo>,
 }
@@ -557,6 +580,10 @@ pub struct SyncConfig {
 
     // Deletion behavior
     pub deletion_behavior: DeletionBehavior,
+
+    // Merge behavior
+    #[serde(default)]
+    pub progress_granularity: ProgressGranularity,
 }
 
 impl Default for SyncConfig {
@@ -574,6 +601,7 @@ impl Default for SyncConfig {
             google_drive_folder: "Manatan".to_string(),
             google_drive_folder_type: GoogleDriveFolderType::Public,
             deletion_behavior: DeletionBehavior::KeepEverywhere,
+            progress_granularity: ProgressGranularity::Chapter,
         }
     }
 }
@@ -597,6 +625,17 @@ pub enum DeletionBehavior {
     AskEachTime,
 }
 
+/// How reading progress from two devices is combined
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ProgressGranularity {
+    /// Only the winning book position is kept
+    Book,
+    /// The winning book position is kept, plus the latest position in every chapter
+    #[default]
+    Chapter,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum SyncBackendType {
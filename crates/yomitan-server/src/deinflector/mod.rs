@@ -124,6 +124,13 @@ impl Deinflector {
         Self { transformers }
     }
 
+    /// Whether `language` has real deinflection rules rather than the empty placeholder.
+    pub fn has_rules(&self, language: Language) -> bool {
+        self.transformers
+            .get(&language)
+            .is_some_and(|transformer| !transformer.is_empty())
+    }
+
     pub fn deinflect(&self, language: Language, text: &str) -> Vec<String> {
         let transformer = self
             .transformers
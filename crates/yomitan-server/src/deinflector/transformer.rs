@@ -165,6 +165,10 @@ impl LanguageTransformer {
         }
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.transforms.is_empty()
+    }
+
     pub fn from_descriptor(descriptor: Descriptor) -> Result<Self> {
         let condition_flags_map = build_condition_flags(&descriptor.conditions)?;
         let mut transforms = Vec::new();
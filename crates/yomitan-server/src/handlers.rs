@@ -1,4 +1,7 @@
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+};
 
 use axum::{
     Json,
@@ -6,16 +9,22 @@ use axum::{
     http::{HeaderMap, StatusCode},
     response::IntoResponse,
 };
+use futures::{StreamExt, stream};
 use regex::Regex;
 use reqwest::Client;
 use scraper::{Html, Selector};
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, Value as JsonValue, json};
 use sha2::{Digest, Sha256};
+use strum::{EnumIter, IntoEnumIterator};
 use tracing::{error, info, warn};
 use wordbase_api::{DictionaryId, Record, Term, dict::yomitan::GlossaryTag};
 
-use crate::{ServerState, import, lookup::KanjiEntry, state::AppState};
+use crate::{
+    ServerState, import,
+    lookup::{KanjiEntry, TermFrequency, TermPitch},
+    state::AppState,
+};
 
 #[cfg(target_os = "ios")]
 unsafe extern "C" {
@@ -23,6 +32,10 @@ unsafe extern "C" {
     fn malloc_zone_pressure_relief(zone: *mut std::ffi::c_void, goal: usize);
 }
 
+/// Upper bound on how many characters after `index` a lookup will scan. The deinflector only
+/// needs the next phrase, so anything beyond this is wasted work.
+const MAX_LOOKUP_SCAN_CHARS: usize = 64;
+
 #[derive(Deserialize)]
 pub struct LookupParams {
     pub text: String,
@@ -30,9 +43,49 @@ pub struct LookupParams {
     // Optional toggle for grouping results (defaults to true in handler)
     pub group: Option<bool>,
     pub language: Option<DictionaryLanguage>,
+    #[serde(default)]
+    pub render: LookupRender,
+    /// Maximum number of term results; defaults to `DEFAULT_LOOKUP_LIMIT`. Must be at least 1.
+    pub limit: Option<usize>,
+    #[serde(default)]
+    pub format: LookupFormat,
+    #[serde(default)]
+    pub by: LookupBy,
 }
 
-#[derive(Deserialize)]
+const DEFAULT_LOOKUP_LIMIT: usize = 200;
+
+#[derive(Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LookupRender {
+    /// Only the structured `furigana` segments are returned.
+    #[default]
+    Structured,
+    /// Each term additionally carries `furiganaHtml`, the headword as `<ruby>` markup.
+    Html,
+}
+
+#[derive(Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LookupFormat {
+    /// The grouped `ApiLookupResponse` shape the reader consumes.
+    #[default]
+    Manatan,
+    /// Yomichan's `termsFind` shape (`definitions` + `length`) for external tools.
+    Yomichan,
+}
+
+#[derive(Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LookupBy {
+    /// Scan `text` from `index` for the longest headword, deinflecting as needed.
+    #[default]
+    Auto,
+    /// Treat the whole of `text` as a reading and return every headword sharing it.
+    Reading,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
 pub enum AudioSource {
     Jpod101,
@@ -49,6 +102,9 @@ pub struct AudioParams {
     pub reading: Option<String>,
     pub source: AudioSource,
     pub language: Option<DictionaryLanguage>,
+    /// When the exact term has no audio, retry with its dictionary base forms.
+    #[serde(default)]
+    pub deinflect: bool,
 }
 
 #[derive(Serialize)]
@@ -56,6 +112,22 @@ pub struct AudioResponse {
     pub url: Option<String>,
 }
 
+#[derive(Deserialize)]
+pub struct AnyAudioParams {
+    pub term: String,
+    pub reading: Option<String>,
+    /// Comma-separated sources, tried in order, e.g. `jisho,jpod101,wiktionary`.
+    pub sources: String,
+    pub language: Option<DictionaryLanguage>,
+}
+
+#[derive(Serialize)]
+pub struct AnyAudioResponse {
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<AudioSource>,
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ApiForm {
@@ -134,6 +206,8 @@ pub struct ApiGroupedResult {
     pub headword: String,
     pub reading: String,
     pub furigana: Vec<(String, String)>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub furigana_html: Option<String>,
     pub glossary: Vec<ApiDefinition>,
     pub frequencies: Vec<ApiFrequency>,
     pub pitch_accents: Vec<ApiPitchAccent>,
@@ -150,6 +224,33 @@ pub struct ApiGroupedResult {
 pub struct ApiLookupResponse {
     pub terms: Vec<ApiGroupedResult>,
     pub kanji: Vec<KanjiEntry>,
+    /// Set only when nothing was found, so the reader can tell why.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<LookupEmptyReason>,
+    /// True when `terms` was cut down to the requested `limit`.
+    pub truncated: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LookupEmptyReason {
+    NoMatch,
+    EmptyDictionaries,
+    Loading,
+}
+
+impl LookupEmptyReason {
+    fn for_state(app_state: &AppState) -> Self {
+        if app_state.is_loading() {
+            return LookupEmptyReason::Loading;
+        }
+        let dicts = app_state.dictionaries.read().expect("lock");
+        if dicts.values().any(|dict| dict.enabled) {
+            LookupEmptyReason::NoMatch
+        } else {
+            LookupEmptyReason::EmptyDictionaries
+        }
+    }
 }
 
 #[derive(Deserialize)]
@@ -160,7 +261,7 @@ pub enum DictionaryAction {
     Reorder { order: Vec<i64> },
 }
 
-#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, EnumIter)]
 #[serde(rename_all = "lowercase")]
 pub enum DictionaryLanguage {
     Japanese,
@@ -937,28 +1038,54 @@ async fn fetch_jisho_audio_url(
 }
 
 pub async fn audio_handler(
+    State(state): State<ServerState>,
     Query(params): Query<AudioParams>,
 ) -> Result<Json<AudioResponse>, (StatusCode, Json<Value>)> {
     let client = Client::new();
+    audio_with(&state, params, |source, term, reading, language| {
+        let client = client.clone();
+        async move {
+            let summary = get_audio_language_summary(language);
+            fetch_audio_url(&client, source, &term, &reading, &summary).await
+        }
+    })
+    .await
+}
+
+/// Body of [`audio_handler`]; `fetch` queries one source for a term, reading and language so
+/// tests can bypass the network.
+async fn audio_with<F, Fut>(
+    state: &ServerState,
+    params: AudioParams,
+    mut fetch: F,
+) -> Result<Json<AudioResponse>, (StatusCode, Json<Value>)>
+where
+    F: FnMut(AudioSource, String, String, DictionaryLanguage) -> Fut,
+    Fut: Future<Output = Result<Option<String>, anyhow::Error>>,
+{
     let term = params.term.trim();
     let reading = params.reading.as_deref().unwrap_or("").trim();
 
-    if term.is_empty() {
+    if term.is_empty() || state.app.is_offline() {
         return Ok(Json(AudioResponse { url: None }));
     }
 
-    let language = params.language.unwrap_or(DictionaryLanguage::Japanese);
-    let summary = get_audio_language_summary(language);
+    let language = resolve_language(&state.app, params.language, None);
 
-    let result = match params.source {
-        AudioSource::Jpod101 => fetch_jpod101_audio_url(&client, term, reading).await,
-        AudioSource::LanguagePod101 => fetch_language_pod101_urls(&client, term, reading, &summary)
-            .await
-            .map(|urls| urls.into_iter().next()),
-        AudioSource::Jisho => fetch_jisho_audio_url(&client, term, reading).await,
-        AudioSource::LinguaLibre => fetch_lingua_libre_audio_url(&client, term, &summary).await,
-        AudioSource::Wiktionary => fetch_wiktionary_audio_url(&client, term, &summary).await,
+    let lemmas = if params.deinflect && !state.app.is_loading() {
+        audio_lemma_candidates(state, term, language)
+    } else {
+        Vec::new()
     };
+    let result = audio_with_lemma_fallback(term, reading, &lemmas, |term, reading| {
+        fetch(
+            params.source,
+            term.to_string(),
+            reading.to_string(),
+            language,
+        )
+    })
+    .await;
 
     match result {
         Ok(url) => Ok(Json(AudioResponse { url })),
@@ -972,6 +1099,172 @@ pub async fn audio_handler(
     }
 }
 
+/// Dictionary headwords (with readings) that `term` deinflects to, excluding `term` itself.
+fn audio_lemma_candidates(
+    state: &ServerState,
+    term: &str,
+    language: DictionaryLanguage,
+) -> Vec<(String, String)> {
+    let term_len = term.chars().count();
+    let mut lemmas: Vec<(String, String)> = Vec::new();
+    for (entry, _) in state
+        .lookup
+        .search(&state.app, term, 0, language.deinflect_language())
+    {
+        if entry.span_chars.end as usize != term_len {
+            continue;
+        }
+        let (headword, reading) = match &entry.term {
+            Term::Full(h, r) => (h.to_string(), r.to_string()),
+            Term::Headword(h) => (h.to_string(), String::new()),
+            Term::Reading(r) => (r.to_string(), String::new()),
+        };
+        if headword == term || lemmas.iter().any(|(h, r)| *h == headword && *r == reading) {
+            continue;
+        }
+        lemmas.push((headword, reading));
+    }
+    lemmas
+}
+
+/// Fetches audio for `term`, then for each base form in `lemmas` until one has audio. Errors on
+/// the exact term are returned as-is; errors on a base form only skip that form.
+async fn audio_with_lemma_fallback<'a, F, Fut>(
+    term: &'a str,
+    reading: &'a str,
+    lemmas: &'a [(String, String)],
+    mut fetch: F,
+) -> Result<Option<String>, anyhow::Error>
+where
+    F: FnMut(&'a str, &'a str) -> Fut,
+    Fut: std::future::Future<Output = Result<Option<String>, anyhow::Error>>,
+{
+    if let Some(url) = fetch(term, reading).await? {
+        return Ok(Some(url));
+    }
+    for (lemma, lemma_reading) in lemmas {
+        match fetch(lemma, lemma_reading).await {
+            Ok(Some(url)) => {
+                info!("🔊 [Audio] Using base form '{}' for '{}'", lemma, term);
+                return Ok(Some(url));
+            }
+            Ok(None) => {}
+            Err(err) => warn!("Audio lookup for base form '{}' failed: {}", lemma, err),
+        }
+    }
+    Ok(None)
+}
+
+async fn fetch_audio_url(
+    client: &Client,
+    source: AudioSource,
+    term: &str,
+    reading: &str,
+    summary: &AudioLanguageSummary,
+) -> Result<Option<String>, anyhow::Error> {
+    match source {
+        AudioSource::Jpod101 => fetch_jpod101_audio_url(client, term, reading).await,
+        AudioSource::LanguagePod101 => fetch_language_pod101_urls(client, term, reading, summary)
+            .await
+            .map(|urls| urls.into_iter().next()),
+        AudioSource::Jisho => fetch_jisho_audio_url(client, term, reading).await,
+        AudioSource::LinguaLibre => fetch_lingua_libre_audio_url(client, term, summary).await,
+        AudioSource::Wiktionary => fetch_wiktionary_audio_url(client, term, summary).await,
+    }
+}
+
+fn parse_audio_sources(raw: &str) -> Result<Vec<AudioSource>, String> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|source| !source.is_empty())
+        .map(|source| {
+            serde_json::from_value(Value::String(source.to_string()))
+                .map_err(|_| format!("Unknown audio source: {source}"))
+        })
+        .collect()
+}
+
+/// Tries `sources` in order and returns the first URL found. Source errors are logged and
+/// skipped so one flaky provider doesn't hide the rest.
+async fn first_audio_url<F, Fut>(
+    sources: &[AudioSource],
+    mut fetch: F,
+) -> Option<(AudioSource, String)>
+where
+    F: FnMut(AudioSource) -> Fut,
+    Fut: std::future::Future<Output = Result<Option<String>, anyhow::Error>>,
+{
+    for &source in sources {
+        match fetch(source).await {
+            Ok(Some(url)) => return Some((source, url)),
+            Ok(None) => {}
+            Err(err) => warn!("Audio lookup via {:?} failed: {}", source, err),
+        }
+    }
+    None
+}
+
+pub async fn audio_any_handler(
+    State(state): State<ServerState>,
+    Query(params): Query<AnyAudioParams>,
+) -> Result<Json<AnyAudioResponse>, (StatusCode, Json<Value>)> {
+    let client = Client::new();
+    audio_any_with(&state, params, |source, term, reading, language| {
+        let client = client.clone();
+        async move {
+            let summary = get_audio_language_summary(language);
+            fetch_audio_url(&client, source, &term, &reading, &summary).await
+        }
+    })
+    .await
+}
+
+/// Body of [`audio_any_handler`]; `fetch` queries one source for a term, reading and language so
+/// tests can bypass the network.
+async fn audio_any_with<F, Fut>(
+    state: &ServerState,
+    params: AnyAudioParams,
+    mut fetch: F,
+) -> Result<Json<AnyAudioResponse>, (StatusCode, Json<Value>)>
+where
+    F: FnMut(AudioSource, String, String, DictionaryLanguage) -> Fut,
+    Fut: Future<Output = Result<Option<String>, anyhow::Error>>,
+{
+    let sources = parse_audio_sources(&params.sources).map_err(|message| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "status": "error", "message": message })),
+        )
+    })?;
+
+    let term = params.term.trim();
+    let reading = params.reading.as_deref().unwrap_or("").trim();
+    if term.is_empty() || sources.is_empty() || state.app.is_offline() {
+        return Ok(Json(AnyAudioResponse {
+            url: None,
+            source: None,
+        }));
+    }
+
+    let language = resolve_language(&state.app, params.language, None);
+
+    let found = first_audio_url(&sources, |source| {
+        fetch(source, term.to_string(), reading.to_string(), language)
+    })
+    .await;
+
+    Ok(Json(match found {
+        Some((source, url)) => AnyAudioResponse {
+            url: Some(url),
+            source: Some(source),
+        },
+        None => AnyAudioResponse {
+            url: None,
+            source: None,
+        },
+    }))
+}
+
 impl std::fmt::Display for DictionaryLanguage {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.as_str())
@@ -983,6 +1276,32 @@ pub struct LanguageRequest {
     pub language: Option<DictionaryLanguage>,
 }
 
+#[derive(Deserialize)]
+pub struct LanguagesRequest {
+    pub languages: Vec<DictionaryLanguage>,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateDictionaryRequest {
+    pub id: Option<i64>,
+    pub language: Option<DictionaryLanguage>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LanguageInstallStatus {
+    Installed,
+    Skipped,
+    Error,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LanguageInstallReport {
+    pub language: DictionaryLanguage,
+    pub status: LanguageInstallStatus,
+    pub message: String,
+}
+
 pub fn load_preferred_language(app_state: &AppState) -> Option<DictionaryLanguage> {
     let conn = app_state.pool.get().ok()?;
     let mut stmt = conn
@@ -994,6 +1313,25 @@ pub fn load_preferred_language(app_state: &AppState) -> Option<DictionaryLanguag
     value.and_then(|val| DictionaryLanguage::from_str(&val))
 }
 
+/// Seeds `preferred_language` from a configured study language (`MANATAN_STUDY_LANGUAGE`) when
+/// none has been stored yet. Returns the language that was stored, if any.
+pub fn seed_preferred_language(
+    app_state: &AppState,
+    study_language: Option<&str>,
+) -> Option<DictionaryLanguage> {
+    let raw = study_language?.trim().to_lowercase();
+    if raw.is_empty() || load_preferred_language(app_state).is_some() {
+        return None;
+    }
+    let Some(language) = DictionaryLanguage::from_str(&raw) else {
+        warn!("⚠️ [Yomitan] Ignoring unknown study language '{}'", raw);
+        return None;
+    };
+    store_preferred_language(app_state, language);
+    info!("🌐 [Yomitan] Preferred language seeded from study language: {language}");
+    Some(language)
+}
+
 fn store_preferred_language(app_state: &AppState, language: DictionaryLanguage) {
     if let Ok(conn) = app_state.pool.get() {
         let _ = conn.execute(
@@ -1003,15 +1341,186 @@ fn store_preferred_language(app_state: &AppState, language: DictionaryLanguage)
     }
 }
 
+fn installed_language_key(language: DictionaryLanguage) -> String {
+    format!("installed_language:{}", language.as_str())
+}
+
+/// Remembers which dictionary a language install produced so later batch installs can tell
+/// whether that language is already present.
+fn record_installed_language(app_state: &AppState, language: DictionaryLanguage, id: DictionaryId) {
+    let name = {
+        let dicts = app_state.dictionaries.read().expect("lock");
+        dicts.get(&id).map(|dict| dict.name.clone())
+    };
+    if let (Some(name), Ok(conn)) = (name, app_state.pool.get()) {
+        let _ = conn.execute(
+            "INSERT OR REPLACE INTO metadata (key, value) VALUES (?, ?)",
+            [installed_language_key(language), name],
+        );
+    }
+}
+
+/// Name of the installed dictionary for `language`. Installs from before the
+/// `installed_language:` metadata existed are recognized by the default archive's title.
+fn installed_language_dictionary(
+    app_state: &AppState,
+    language: DictionaryLanguage,
+) -> Option<String> {
+    let conn = app_state.pool.get().ok()?;
+    let recorded: Option<String> = conn
+        .query_row(
+            "SELECT value FROM metadata WHERE key = ?",
+            [installed_language_key(language)],
+            |row| row.get(0),
+        )
+        .ok();
+    let dicts = app_state.dictionaries.read().expect("lock");
+    match recorded {
+        Some(name) => dicts.values().any(|dict| dict.name == name).then_some(name),
+        None => dicts
+            .values()
+            .filter(|dict| is_default_dictionary_title(&dict.name, language))
+            .min_by_key(|dict| dict.id.0)
+            .map(|dict| dict.name.clone()),
+    }
+}
+
+/// Title of the archive [`dictionary_url`] serves, up to any version suffix: kaikki-to-yomitan
+/// archives are titled after their file name, JMdict and CC-CEDICT carry a release tag.
+fn default_dictionary_title(language: DictionaryLanguage) -> &'static str {
+    match language {
+        DictionaryLanguage::Japanese => "JMdict",
+        DictionaryLanguage::Chinese => "CC-CEDICT",
+        _ => {
+            let url = dictionary_url(language);
+            let file = url.rsplit('/').next().unwrap_or(url);
+            file.strip_suffix(".zip").unwrap_or(file)
+        }
+    }
+}
+
+fn is_default_dictionary_title(name: &str, language: DictionaryLanguage) -> bool {
+    name.strip_prefix(default_dictionary_title(language))
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with([' ', '[', '(']))
+}
+
+/// Language whose download source dictionary `id` came from, falling back to the default
+/// archive titles for installs that predate the `installed_language:` metadata.
+fn language_for_dictionary(app_state: &AppState, id: DictionaryId) -> Option<DictionaryLanguage> {
+    let name = {
+        let dicts = app_state.dictionaries.read().expect("lock");
+        dicts.get(&id)?.name.clone()
+    };
+    let conn = app_state.pool.get().ok()?;
+    let recorded = conn
+        .query_row(
+            "SELECT key FROM metadata WHERE key LIKE 'installed_language:%' AND value = ?",
+            [&name],
+            |row| row.get::<_, String>(0),
+        )
+        .ok()
+        .and_then(|key| DictionaryLanguage::from_str(key.strip_prefix("installed_language:")?));
+    recorded.or_else(|| {
+        DictionaryLanguage::iter().find(|&language| is_default_dictionary_title(&name, language))
+    })
+}
+
+fn dictionary_id_by_name(app_state: &AppState, name: &str) -> Option<DictionaryId> {
+    let dicts = app_state.dictionaries.read().expect("lock");
+    dicts
+        .values()
+        .find(|dict| dict.name == name)
+        .map(|dict| dict.id)
+}
+
+/// Header the OCR overlay uses to pass the detected language along with a lookup.
+const LANGUAGE_HEADER: &str = "x-manatan-language";
+
+/// Lookup precedence: query param > `X-Manatan-Language` header > stored preference > script
+/// auto-detection.
+fn lookup_language(
+    app_state: &AppState,
+    query: Option<DictionaryLanguage>,
+    headers: &HeaderMap,
+    text: &str,
+) -> DictionaryLanguage {
+    let from_header = || {
+        headers
+            .get(LANGUAGE_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(DictionaryLanguage::from_str)
+    };
+    resolve_language(app_state, query.or_else(from_header), Some(text))
+}
+
+/// Explicit `language` wins, then the stored preference, then a guess from `sample`'s script.
 fn resolve_language(
     app_state: &AppState,
     language: Option<DictionaryLanguage>,
+    sample: Option<&str>,
 ) -> DictionaryLanguage {
     language
         .or_else(|| load_preferred_language(app_state))
+        .or_else(|| sample.and_then(detect_language))
         .unwrap_or(DictionaryLanguage::Japanese)
 }
 
+/// Simplified characters that don't appear in Japanese text, used to tell Chinese from kanji-only
+/// Japanese.
+const SIMPLIFIED_ONLY_HAN: &str = "们这么说个为让还对没样门东见长认给吗呢种钱车书时";
+
+/// Guesses a language from the writing system of `text`. Kana and Hangul decide immediately;
+/// Han, Cyrillic and Latin are weighed only after the whole text has been seen.
+fn detect_language(text: &str) -> Option<DictionaryLanguage> {
+    let mut has_han = false;
+    let mut simplified = false;
+    let mut has_cyrillic = false;
+    let mut has_latin = false;
+
+    for ch in text.chars() {
+        match ch as u32 {
+            0x3040..=0x30FF | 0x31F0..=0x31FF | 0xFF66..=0xFF9F => {
+                return Some(DictionaryLanguage::Japanese);
+            }
+            0x1100..=0x11FF | 0x3130..=0x318F | 0xAC00..=0xD7AF => {
+                return Some(DictionaryLanguage::Korean);
+            }
+            0x3400..=0x4DBF | 0x4E00..=0x9FFF => {
+                has_han = true;
+                simplified |= SIMPLIFIED_ONLY_HAN.contains(ch);
+            }
+            // і ї є ґ only occur in Ukrainian among the supported Cyrillic languages.
+            0x0454 | 0x0456 | 0x0457 | 0x0491 => return Some(DictionaryLanguage::Ukrainian),
+            0x0400..=0x04FF => has_cyrillic = true,
+            0x0370..=0x03FF => return Some(DictionaryLanguage::Greek),
+            0x0590..=0x05FF => return Some(DictionaryLanguage::Hebrew),
+            0x0600..=0x06FF => return Some(DictionaryLanguage::Arabic),
+            0x0900..=0x097F => return Some(DictionaryLanguage::Hindi),
+            0x0C80..=0x0CFF => return Some(DictionaryLanguage::Kannada),
+            0x0E00..=0x0E7F => return Some(DictionaryLanguage::Thai),
+            0x0E80..=0x0EFF => return Some(DictionaryLanguage::Lao),
+            0x10A0..=0x10FF => return Some(DictionaryLanguage::Georgian),
+            0x1780..=0x17FF => return Some(DictionaryLanguage::Khmer),
+            _ if ch.is_ascii_alphabetic() => has_latin = true,
+            _ => {}
+        }
+    }
+
+    if has_han {
+        Some(if simplified {
+            DictionaryLanguage::Chinese
+        } else {
+            DictionaryLanguage::Japanese
+        })
+    } else if has_cyrillic {
+        Some(DictionaryLanguage::Russian)
+    } else if has_latin {
+        Some(DictionaryLanguage::English)
+    } else {
+        None
+    }
+}
+
 fn dictionary_url(language: DictionaryLanguage) -> &'static str {
     match language {
         DictionaryLanguage::Japanese => {
@@ -1143,90 +1652,475 @@ fn dictionary_url(language: DictionaryLanguage) -> &'static str {
     }
 }
 
-async fn download_dictionary_bytes(language: DictionaryLanguage) -> Result<Vec<u8>, String> {
-    const MAX_DOWNLOAD_BYTES: u64 = 384 * 1024 * 1024;
+const MAX_DOWNLOAD_BYTES: u64 = 384 * 1024 * 1024;
+const DOWNLOAD_MAX_ATTEMPTS: u32 = 5;
+#[cfg(test)]
+const DOWNLOAD_RETRY_BACKOFF: std::time::Duration = std::time::Duration::from_millis(10);
+#[cfg(not(test))]
+const DOWNLOAD_RETRY_BACKOFF: std::time::Duration = std::time::Duration::from_secs(2);
+
+enum DownloadError {
+    /// The connection dropped or the body was truncated; retrying with a `Range` header may help.
+    Retry(String),
+    /// The server rejected the request or the archive violates a limit; retrying is pointless.
+    Fatal(String),
+}
 
-    let url = dictionary_url(language);
-    let client = Client::new();
-    let response = client
-        .get(url)
-        .send()
-        .await
-        .map_err(|e| format!("Dictionary download failed: {e}"))?;
+/// Downloads the dictionary for `language` once a download slot is free.
+async fn download_dictionary_bytes(
+    app_state: &AppState,
+    language: DictionaryLanguage,
+) -> Result<Vec<u8>, String> {
+    download_dictionary_bytes_with(app_state, language, |app, language| async move {
+        fetch_dictionary_bytes(&app, language).await
+    })
+    .await
+}
 
-    if !response.status().is_success() {
-        return Err(format!(
-            "Dictionary download failed ({}): {url}",
-            response.status()
-        ));
+/// Runs `fetch` for `language` once a download slot is free. When downloads are disabled it fails
+/// straight away instead of queueing for a slot first.
+async fn download_dictionary_bytes_with<F, Fut>(
+    app_state: &AppState,
+    language: DictionaryLanguage,
+    fetch: F,
+) -> Result<Vec<u8>, String>
+where
+    F: FnOnce(AppState, DictionaryLanguage) -> Fut,
+    Fut: Future<Output = Result<Vec<u8>, String>>,
+{
+    ensure_downloads_enabled(app_state)?;
+    let _permit = app_state.acquire_download_permit().await;
+    fetch(app_state.clone(), language).await
+}
+
+fn ensure_downloads_enabled(app_state: &AppState) -> Result<(), String> {
+    if app_state.is_offline() {
+        return Err(
+            "Dictionary downloads are disabled in offline mode (MANATAN_OFFLINE).".to_string(),
+        );
+    }
+    if !app_state.dict_downloads_allowed() {
+        return Err(
+            "Dictionary downloads are disabled (MANATAN_ALLOW_DICT_DOWNLOAD=false). Upload a dictionary zip instead."
+                .to_string(),
+        );
     }
+    Ok(())
+}
 
-    if let Some(content_length) = response.content_length()
-        && content_length > MAX_DOWNLOAD_BYTES
-    {
+/// Downloads the dictionary for `language`. Callers must hold a download slot and have checked
+/// [`ensure_downloads_enabled`].
+async fn fetch_dictionary_bytes(
+    app_state: &AppState,
+    language: DictionaryLanguage,
+) -> Result<Vec<u8>, String> {
+    let url = dictionary_url(language);
+    let partial_path = partial_download_path(&app_state.data_dir, language, url);
+    let Some(_claim) = app_state.claim_download(&partial_path) else {
         return Err(format!(
-            "Dictionary archive is too large ({content_length} bytes, max {MAX_DOWNLOAD_BYTES})."
+            "The {language} dictionary is already being downloaded."
         ));
-    }
+    };
+
+    let client = Client::new();
+    download_resumable(&client, url, &partial_path, MAX_DOWNLOAD_BYTES).await?;
 
-    let bytes = response
-        .bytes()
+    let bytes = tokio::fs::read(&partial_path)
         .await
         .map_err(|e| format!("Failed to read dictionary bytes: {e}"))?;
+    remove_partial_download(&partial_path).await;
 
-    if bytes.len() as u64 > MAX_DOWNLOAD_BYTES {
-        return Err(format!(
-            "Dictionary archive is too large ({} bytes, max {MAX_DOWNLOAD_BYTES}).",
-            bytes.len()
-        ));
-    }
+    Ok(bytes)
+}
 
-    Ok(bytes.to_vec())
+/// Where an in-progress download of `url` is kept. The directory is private to the dictionary
+/// server, and the name carries a hash of the URL so a changed source never resumes onto bytes
+/// from the old one.
+fn partial_download_path(
+    storage_dir: &std::path::Path,
+    language: DictionaryLanguage,
+    url: &str,
+) -> std::path::PathBuf {
+    let url_hash = format!("{:x}", Sha256::digest(url.as_bytes()));
+    storage_dir
+        .join("dict_downloads")
+        .join(format!("{language}-{}.zip.part", &url_hash[..16]))
 }
 
-fn clear_dictionary_state(app_state: &AppState) {
-    let mut dicts = app_state.dictionaries.write().expect("lock");
-    dicts.clear();
-    let mut next_id = app_state.next_dict_id.write().expect("lock");
-    *next_id = 1;
+/// Sidecar holding the `ETag`/`Last-Modified` of the archive a partial download belongs to.
+fn validator_path(dest: &std::path::Path) -> std::path::PathBuf {
+    let mut path = dest.as_os_str().to_owned();
+    path.push(".validator");
+    path.into()
+}
 
-    if let Ok(mut conn) = app_state.pool.get() {
-        if let Ok(tx) = conn.transaction() {
-            let _ = tx.execute("DELETE FROM terms", []);
-            let _ = tx.execute("DELETE FROM dictionaries", []);
-            let _ = tx.execute("DELETE FROM metadata", []);
-            let _ = tx.commit();
-        }
-        info!("🧹 [Yomitan] Vacuuming after reset...");
-        let _ = conn.execute("VACUUM", []);
-    }
+async fn remove_partial_download(dest: &std::path::Path) {
+    let _ = tokio::fs::remove_file(dest).await;
+    let _ = tokio::fs::remove_file(validator_path(dest)).await;
 }
 
-async fn wait_for_startup_guard(app_state: &AppState, operation: &str) {
-    if app_state.is_import_startup_guard_active() {
-        let remaining = app_state.import_startup_guard_remaining_secs();
-        let wait_secs = remaining.max(1);
-        warn!(
-            "⏳ [Yomitan] Delaying {operation} until startup guard expires (remaining: {remaining}s)"
-        );
-        tokio::time::sleep(std::time::Duration::from_secs(wait_secs)).await;
-    }
+/// A strong `ETag`, else `Last-Modified`: the values `If-Range` accepts.
+fn response_validator(response: &reqwest::Response) -> Option<String> {
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    };
+    header(reqwest::header::ETAG)
+        .filter(|etag| !etag.starts_with("W/"))
+        .or_else(|| header(reqwest::header::LAST_MODIFIED))
+        .map(str::to_string)
 }
 
-pub async fn install_language_internal(
-    app_state: AppState,
-    language: DictionaryLanguage,
-) -> Result<String, String> {
-    let dict_bytes = download_dictionary_bytes(language).await?;
-    let app_state_for_task = app_state.clone();
-    let res =
-        tokio::task::spawn_blocking(move || import::import_zip(&app_state_for_task, &dict_bytes))
+/// Downloads `url` into `dest`, resuming from whatever is already on disk.
+///
+/// Each retry sends `Range: bytes=<received>-` with `If-Range` set to the validator recorded when
+/// the download started, so a dropped connection only costs the bytes that were in flight and a
+/// changed archive restarts from zero. The final file size is checked against the advertised
+/// total.
+async fn download_resumable(
+    client: &Client,
+    url: &str,
+    dest: &std::path::Path,
+    max_bytes: u64,
+) -> Result<(), String> {
+    if let Some(parent) = dest.parent() {
+        tokio::fs::create_dir_all(parent)
             .await
-            .map_err(|e| e.to_string())?;
-    res.map_err(|e| e.to_string())
+            .map_err(|e| format!("Failed to create download directory: {e}"))?;
+    }
+
+    let mut last_err = String::new();
+    for attempt in 1..=DOWNLOAD_MAX_ATTEMPTS {
+        match download_attempt(client, url, dest, max_bytes).await {
+            Ok(()) => return Ok(()),
+            Err(DownloadError::Fatal(e)) => {
+                remove_partial_download(dest).await;
+                return Err(e);
+            }
+            Err(DownloadError::Retry(e)) => {
+                warn!(
+                    "⚠️ [Yomitan] Dictionary download interrupted (attempt {attempt}/{DOWNLOAD_MAX_ATTEMPTS}): {e}"
+                );
+                last_err = e;
+                if attempt < DOWNLOAD_MAX_ATTEMPTS {
+                    tokio::time::sleep(DOWNLOAD_RETRY_BACKOFF * attempt).await;
+                }
+            }
+        }
+    }
+
+    remove_partial_download(dest).await;
+    Err(format!("Dictionary download failed: {last_err}"))
 }
 
-pub async fn manage_dictionaries_handler(
+async fn download_attempt(
+    client: &Client,
+    url: &str,
+    dest: &std::path::Path,
+    max_bytes: u64,
+) -> Result<(), DownloadError> {
+    use tokio::io::AsyncWriteExt;
+
+    let mut received = tokio::fs::metadata(dest)
+        .await
+        .map(|meta| meta.len())
+        .unwrap_or(0);
+    let validator = match received {
+        0 => None,
+        _ => tokio::fs::read_to_string(validator_path(dest))
+            .await
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty()),
+    };
+    if received > 0 && validator.is_none() {
+        // Nothing ties these bytes to the archive the server has now, so don't build on them.
+        remove_partial_download(dest).await;
+        received = 0;
+    }
+
+    let mut request = client.get(url);
+    if let Some(validator) = &validator {
+        request = request
+            .header(reqwest::header::RANGE, format!("bytes={received}-"))
+            .header(reqwest::header::IF_RANGE, validator.as_str());
+    }
+
+    let mut response = request
+        .send()
+        .await
+        .map_err(|e| DownloadError::Retry(e.to_string()))?;
+    let status = response.status();
+
+    let total = if status == reqwest::StatusCode::PARTIAL_CONTENT {
+        if let Some(current) = response_validator(&response)
+            && validator.as_deref() != Some(current.as_str())
+        {
+            remove_partial_download(dest).await;
+            return Err(DownloadError::Retry(
+                "archive changed on the server; restarting".to_string(),
+            ));
+        }
+        response
+            .headers()
+            .get(reqwest::header::CONTENT_RANGE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_content_range_total)
+            .or_else(|| response.content_length().map(|len| received + len))
+    } else if status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE && received > 0 {
+        // Either the file is already complete or the partial data is stale; start over if the
+        // server's advertised size does not match what we have.
+        let total = response
+            .headers()
+            .get(reqwest::header::CONTENT_RANGE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_content_range_total);
+        if total == Some(received) {
+            return Ok(());
+        }
+        remove_partial_download(dest).await;
+        return Err(DownloadError::Retry(
+            "server rejected resume range; restarting".to_string(),
+        ));
+    } else if status.is_success() {
+        // The server ignored the range, the archive changed (If-Range failed) or this is the
+        // first attempt: restart from zero and remember which archive these bytes belong to.
+        received = 0;
+        let recorded = match response_validator(&response) {
+            Some(current) => tokio::fs::write(validator_path(dest), current).await,
+            None => match tokio::fs::remove_file(validator_path(dest)).await {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            },
+        };
+        recorded.map_err(|e| DownloadError::Fatal(format!("Failed to record download: {e}")))?;
+        response.content_length()
+    } else {
+        return Err(DownloadError::Fatal(format!(
+            "Dictionary download failed ({status}): {url}"
+        )));
+    };
+
+    if let Some(total) = total
+        && total > max_bytes
+    {
+        return Err(DownloadError::Fatal(format!(
+            "Dictionary archive is too large ({total} bytes, max {max_bytes})."
+        )));
+    }
+
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(received > 0)
+        .truncate(received == 0)
+        .open(dest)
+        .await
+        .map_err(|e| DownloadError::Fatal(format!("Failed to open download file: {e}")))?;
+
+    loop {
+        let chunk = match response.chunk().await {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => break,
+            Err(e) => {
+                let _ = file.flush().await;
+                return Err(DownloadError::Retry(e.to_string()));
+            }
+        };
+
+        received += chunk.len() as u64;
+        if received > max_bytes {
+            return Err(DownloadError::Fatal(format!(
+                "Dictionary archive is too large ({received} bytes, max {max_bytes})."
+            )));
+        }
+
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| DownloadError::Fatal(format!("Failed to write download file: {e}")))?;
+    }
+
+    file.flush()
+        .await
+        .map_err(|e| DownloadError::Fatal(format!("Failed to write download file: {e}")))?;
+
+    match total {
+        Some(total) if received < total => Err(DownloadError::Retry(format!(
+            "download truncated ({received} of {total} bytes)"
+        ))),
+        Some(total) if received > total => {
+            remove_partial_download(dest).await;
+            Err(DownloadError::Retry(format!(
+                "download overran advertised size ({received} of {total} bytes)"
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Extracts the complete length from a `Content-Range` header (`bytes 100-199/200` or
+/// `bytes */200`).
+fn parse_content_range_total(value: &str) -> Option<u64> {
+    value.rsplit_once('/')?.1.trim().parse().ok()
+}
+
+fn clear_dictionary_state(app_state: &AppState) {
+    let mut dicts = app_state.dictionaries.write().expect("lock");
+    dicts.clear();
+    let mut next_id = app_state.next_dict_id.write().expect("lock");
+    *next_id = 1;
+
+    if let Ok(mut conn) = app_state.pool.get() {
+        if let Ok(tx) = conn.transaction() {
+            let _ = tx.execute("DELETE FROM terms", []);
+            let _ = tx.execute("DELETE FROM term_frequencies", []);
+            let _ = tx.execute("DELETE FROM term_pitches", []);
+            let _ = tx.execute("DELETE FROM dictionaries", []);
+            let _ = tx.execute("DELETE FROM metadata", []);
+            let _ = tx.commit();
+        }
+        info!("🧹 [Yomitan] Vacuuming after reset...");
+        let _ = conn.execute("VACUUM", []);
+    }
+}
+
+async fn wait_for_startup_guard(app_state: &AppState, operation: &str) {
+    if app_state.is_import_startup_guard_active() {
+        let remaining = app_state.import_startup_guard_remaining_secs();
+        let wait_secs = remaining.max(1);
+        warn!(
+            "⏳ [Yomitan] Delaying {operation} until startup guard expires (remaining: {remaining}s)"
+        );
+        tokio::time::sleep(std::time::Duration::from_secs(wait_secs)).await;
+    }
+}
+
+pub async fn install_language_internal(
+    app_state: AppState,
+    language: DictionaryLanguage,
+) -> Result<String, String> {
+    let dict_bytes = download_dictionary_bytes(&app_state, language).await?;
+    install_language_bytes(app_state, language, dict_bytes).await
+}
+
+async fn install_language_bytes(
+    app_state: AppState,
+    language: DictionaryLanguage,
+    dict_bytes: Vec<u8>,
+) -> Result<String, String> {
+    let app_state_for_task = app_state.clone();
+    let res = tokio::task::spawn_blocking(move || {
+        import::import_archive(&app_state_for_task, &dict_bytes, None)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    let (id, msg) = res.map_err(|e| e.to_string())?;
+    record_installed_language(&app_state, language, id);
+    Ok(msg)
+}
+
+async fn update_language_bytes(
+    app_state: AppState,
+    language: DictionaryLanguage,
+    id: DictionaryId,
+    dict_bytes: Vec<u8>,
+) -> Result<String, String> {
+    let app_state_for_task = app_state.clone();
+    let res = tokio::task::spawn_blocking(move || {
+        import::import_archive(&app_state_for_task, &dict_bytes, Some(id))
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    let (new_id, msg) = res.map_err(|e| e.to_string())?;
+    record_installed_language(&app_state, language, new_id);
+    Ok(msg)
+}
+
+/// Installs each language in order, skipping languages whose dictionary is already loaded.
+/// Up to [`AppState::max_concurrent_downloads`] archives download ahead of the import, each
+/// holding a download slot while it transfers. `fetch` supplies the archive bytes so tests can
+/// bypass the network.
+async fn install_languages_with<F, Fut>(
+    app_state: &AppState,
+    languages: &[DictionaryLanguage],
+    fetch: F,
+) -> Vec<LanguageInstallReport>
+where
+    F: Fn(AppState, DictionaryLanguage) -> Fut,
+    Fut: Future<Output = Result<Vec<u8>, String>>,
+{
+    let mut reports = Vec::with_capacity(languages.len());
+    let mut seen = HashSet::new();
+    let unique: Vec<DictionaryLanguage> = languages
+        .iter()
+        .copied()
+        .filter(|language| seen.insert(language.as_str()))
+        .collect();
+
+    let mut downloads = stream::iter(unique)
+        .map(|language| {
+            let installed = installed_language_dictionary(app_state, language);
+            let download = installed
+                .is_none()
+                .then(|| fetch(app_state.clone(), language));
+            let app_state = app_state.clone();
+            async move {
+                let fetched = match download {
+                    Some(download) => match ensure_downloads_enabled(&app_state) {
+                        Ok(()) => {
+                            let _permit = app_state.acquire_download_permit().await;
+                            download.await
+                        }
+                        Err(e) => Err(e),
+                    },
+                    None => Ok(Vec::new()),
+                };
+                (language, installed, fetched)
+            }
+        })
+        .buffered(app_state.max_concurrent_downloads());
+
+    while let Some((language, installed, fetched)) = downloads.next().await {
+        if let Some(name) = installed {
+            reports.push(LanguageInstallReport {
+                language,
+                status: LanguageInstallStatus::Skipped,
+                message: format!("Dictionary '{name}' is already installed."),
+            });
+            continue;
+        }
+
+        info!("📥 [Yomitan] Installing dictionary ({language})...");
+        let res = match fetched {
+            Ok(bytes) => install_language_bytes(app_state.clone(), language, bytes).await,
+            Err(e) => Err(e),
+        };
+
+        reports.push(match res {
+            Ok(msg) => LanguageInstallReport {
+                language,
+                status: LanguageInstallStatus::Installed,
+                message: msg,
+            },
+            Err(e) => {
+                error!("❌ [Install Languages] {language} failed: {}", e);
+                LanguageInstallReport {
+                    language,
+                    status: LanguageInstallStatus::Error,
+                    message: e,
+                }
+            }
+        });
+    }
+
+    reports
+}
+
+pub async fn manage_dictionaries_handler(
     State(state): State<ServerState>,
     Json(action): Json<DictionaryAction>,
 ) -> Json<Value> {
@@ -1263,6 +2157,18 @@ pub async fn manage_dictionaries_handler(
                     )
                     .map_err(|e| e.to_string())?;
 
+                    tx.execute(
+                        "DELETE FROM term_frequencies WHERE dictionary_id = ?",
+                        rusqlite::params![id],
+                    )
+                    .map_err(|e| e.to_string())?;
+
+                    tx.execute(
+                        "DELETE FROM term_pitches WHERE dictionary_id = ?",
+                        rusqlite::params![id],
+                    )
+                    .map_err(|e| e.to_string())?;
+
                     tx.execute(
                         "DELETE FROM kanji WHERE dictionary_id = ?",
                         rusqlite::params![id],
@@ -1357,10 +2263,29 @@ pub async fn install_defaults_handler(
     State(state): State<ServerState>,
     payload: Option<Json<LanguageRequest>>,
 ) -> Json<Value> {
-    let app_state = state.app.clone();
+    install_defaults_with(
+        &state.app,
+        payload.and_then(|val| val.0.language),
+        |app, language| async move { fetch_dictionary_bytes(&app, language).await },
+    )
+    .await
+}
+
+/// Body of [`install_defaults_handler`]; `fetch` supplies the archive bytes so tests can bypass
+/// the network.
+async fn install_defaults_with<F, Fut>(
+    app_state: &AppState,
+    language: Option<DictionaryLanguage>,
+    fetch: F,
+) -> Json<Value>
+where
+    F: FnOnce(AppState, DictionaryLanguage) -> Fut,
+    Fut: Future<Output = Result<Vec<u8>, String>>,
+{
+    let app_state = app_state.clone();
     wait_for_startup_guard(&app_state, "install-defaults").await;
 
-    let language = resolve_language(&app_state, payload.and_then(|val| val.0.language));
+    let language = resolve_language(&app_state, language, None);
 
     {
         let dicts = app_state.dictionaries.read().expect("lock");
@@ -1373,7 +2298,10 @@ pub async fn install_defaults_handler(
     info!("📥 [Yomitan] User requested dictionary install ({language})...");
     app_state.set_loading(true);
 
-    let res = install_language_internal(app_state.clone(), language).await;
+    let res = match download_dictionary_bytes_with(&app_state, language, fetch).await {
+        Ok(bytes) => install_language_bytes(app_state.clone(), language, bytes).await,
+        Err(e) => Err(e),
+    };
 
     app_state.set_loading(false);
 
@@ -1396,7 +2324,7 @@ pub async fn install_language_handler(
     let app_state = state.app.clone();
     wait_for_startup_guard(&app_state, "install-language").await;
 
-    let language = resolve_language(&app_state, payload.and_then(|val| val.0.language));
+    let language = resolve_language(&app_state, payload.and_then(|val| val.0.language), None);
 
     {
         let dicts = app_state.dictionaries.read().expect("lock");
@@ -1425,6 +2353,104 @@ pub async fn install_language_handler(
     }
 }
 
+pub async fn install_languages_handler(
+    State(state): State<ServerState>,
+    Json(payload): Json<LanguagesRequest>,
+) -> Json<Value> {
+    let app_state = state.app.clone();
+    wait_for_startup_guard(&app_state, "install-languages").await;
+
+    app_state.set_loading(true);
+    let reports =
+        install_languages_with(&app_state, &payload.languages, |app, language| async move {
+            fetch_dictionary_bytes(&app, language).await
+        })
+        .await;
+    app_state.set_loading(false);
+
+    if load_preferred_language(&app_state).is_none()
+        && let Some(report) = reports
+            .iter()
+            .find(|report| report.status == LanguageInstallStatus::Installed)
+    {
+        store_preferred_language(&app_state, report.language);
+    }
+
+    let failed = reports
+        .iter()
+        .any(|report| report.status == LanguageInstallStatus::Error);
+    Json(json!({
+        "status": if failed { "error" } else { "ok" },
+        "results": reports,
+    }))
+}
+
+pub async fn update_dictionary_handler(
+    State(state): State<ServerState>,
+    Json(payload): Json<UpdateDictionaryRequest>,
+) -> Json<Value> {
+    update_dictionary_with(&state.app, payload, |app, language| async move {
+        fetch_dictionary_bytes(&app, language).await
+    })
+    .await
+}
+
+/// Body of [`update_dictionary_handler`]; `fetch` supplies the archive bytes so tests can bypass
+/// the network.
+async fn update_dictionary_with<F, Fut>(
+    app_state: &AppState,
+    payload: UpdateDictionaryRequest,
+    fetch: F,
+) -> Json<Value>
+where
+    F: FnOnce(AppState, DictionaryLanguage) -> Fut,
+    Fut: Future<Output = Result<Vec<u8>, String>>,
+{
+    let app_state = app_state.clone();
+    wait_for_startup_guard(&app_state, "update-dictionary").await;
+
+    let target = match (payload.id, payload.language) {
+        (Some(id), _) => {
+            let id = DictionaryId(id);
+            language_for_dictionary(&app_state, id)
+                .map(|language| (id, language))
+                .ok_or_else(|| {
+                    format!(
+                        "Dictionary {} has no known download source to update from.",
+                        id.0
+                    )
+                })
+        }
+        (None, Some(language)) => installed_language_dictionary(&app_state, language)
+            .and_then(|name| dictionary_id_by_name(&app_state, &name))
+            .map(|id| (id, language))
+            .ok_or_else(|| format!("No installed dictionary found for {language}.")),
+        (None, None) => Err("Either 'id' or 'language' is required.".to_string()),
+    };
+    let (id, language) = match target {
+        Ok(target) => target,
+        Err(e) => return Json(json!({ "status": "error", "message": e })),
+    };
+
+    info!("🔄 [Yomitan] Updating dictionary {} ({language})...", id.0);
+    app_state.set_loading(true);
+
+    let res = match download_dictionary_bytes_with(&app_state, language, fetch).await {
+        Ok(bytes) => update_language_bytes(app_state.clone(), language, id, bytes).await,
+        Err(e) => Err(e),
+    };
+
+    app_state.set_loading(false);
+
+    match res {
+        Ok(msg) => Json(json!({ "status": "ok", "message": msg })),
+        Err(e) => {
+            error!("❌ [Update Dictionary] Failed: {}", e);
+            Json(json!({ "status": "error", "message": e }))
+        }
+    }
+}
+
 pub async fn reset_db_handler(
     State(state): State<ServerState>,
     payload: Option<Json<LanguageRequest>>,
@@ -1432,7 +2458,7 @@ pub async fn reset_db_handler(
     let app_state = state.app.clone();
     wait_for_startup_guard(&app_state, "reset").await;
 
-    let language = resolve_language(&app_state, payload.and_then(|val| val.0.language));
+    let language = resolve_language(&app_state, payload.and_then(|val| val.0.language), None);
     info!("🧨 [Yomitan] Resetting Database ({language})...");
     state.app.set_loading(true);
 
@@ -1463,32 +2489,127 @@ pub async fn reset_db_handler(
     }
 }
 
+/// Entry point for `/lookup`: runs the lookup and reshapes it when `format=yomichan`.
+pub async fn lookup_route_handler(
+    state: State<ServerState>,
+    headers: HeaderMap,
+    Query(params): Query<LookupParams>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let format = params.format;
+    let Json(response) = lookup_handler(state, headers, Query(params)).await?;
+    let body = match format {
+        LookupFormat::Manatan => json!(response),
+        LookupFormat::Yomichan => yomichan_terms_find(&response),
+    };
+    Ok(Json(body))
+}
+
+/// Reshapes a grouped lookup into Yomichan's `termsFind` result: one entry per term with its
+/// `expression`, `reading`, `termTags` and per-dictionary `definitions`.
+fn yomichan_terms_find(response: &ApiLookupResponse) -> Value {
+    let definitions: Vec<Value> = response
+        .terms
+        .iter()
+        .map(|term| {
+            let term_tags: Vec<&str> = term.term_tags.iter().map(|tag| tag.name.as_str()).collect();
+            let definitions: Vec<Value> = term
+                .glossary
+                .iter()
+                .map(|def| {
+                    let glossary = match &def.content {
+                        Value::Array(items) => items.clone(),
+                        other => vec![other.clone()],
+                    };
+                    json!({
+                        "dictionary": def.dictionary_name,
+                        "glossary": glossary,
+                        "definitionTags": def.tags,
+                    })
+                })
+                .collect();
+            let frequencies: Vec<Value> = term
+                .frequencies
+                .iter()
+                .map(|freq| json!({ "dictionary": freq.dictionary_name, "frequency": freq.value }))
+                .collect();
+            json!({
+                "expression": term.headword,
+                "reading": term.reading,
+                "source": term.headword,
+                "termTags": term_tags,
+                "definitions": definitions,
+                "frequencies": frequencies,
+            })
+        })
+        .collect();
+    let length = response
+        .terms
+        .iter()
+        .map(|term| term.match_len)
+        .max()
+        .unwrap_or(0);
+    json!({ "definitions": definitions, "length": length })
+}
+
 #[allow(clippy::useless_let_if_seq)]
 pub async fn lookup_handler(
     State(state): State<ServerState>,
+    headers: HeaderMap,
     Query(params): Query<LookupParams>,
 ) -> Result<Json<ApiLookupResponse>, (StatusCode, Json<Value>)> {
     let cursor_idx = params.index.unwrap_or(0);
-    let language = params
-        .language
-        .or_else(|| load_preferred_language(&state.app))
-        .unwrap_or(DictionaryLanguage::Japanese);
+    let Some(scan_text) = lookup_scan_window(&params.text, cursor_idx) else {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": "invalid_index",
+                "message": format!(
+                    "index {cursor_idx} is out of range for text of {} bytes",
+                    params.text.len()
+                ),
+            })),
+        ));
+    };
+    let language = lookup_language(&state.app, params.language, &headers, &params.text);
     // determine if we should group results or return raw dictionary entries
     let should_group = params.group.unwrap_or(true);
+    let limit = params.limit.unwrap_or(DEFAULT_LOOKUP_LIMIT);
+    if limit == 0 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": "invalid_limit",
+                "message": "limit must be at least 1",
+            })),
+        ));
+    }
 
     if state.app.is_loading() {
         return Err((
             StatusCode::SERVICE_UNAVAILABLE,
-            Json(json!({ "error": "loading", "message": "Dictionaries are importing..." })),
+            Json(json!({
+                "error": "loading",
+                "message": "Dictionaries are importing...",
+                "terms": [],
+                "kanji": [],
+                "reason": LookupEmptyReason::Loading,
+                "truncated": false,
+            })),
         ));
     }
 
-    let raw_results = state.lookup.search(
-        &state.app,
-        &params.text,
-        cursor_idx,
-        language.deinflect_language(),
-    );
+    let raw_results = match params.by {
+        LookupBy::Auto => {
+            state
+                .lookup
+                .search(&state.app, scan_text, 0, language.deinflect_language())
+        }
+        LookupBy::Reading => {
+            state
+                .lookup
+                .search_by_reading(&state.app, scan_text, language.deinflect_language())
+        }
+    };
 
     let dict_meta: std::collections::HashMap<DictionaryId, (String, Option<String>)> = {
         let dicts = state.app.dictionaries.read().expect("lock");
@@ -1749,6 +2870,7 @@ pub async fn lookup_handler(
                     headword: headword.clone(),
                     reading: reading.clone(),
                     furigana: calculate_furigana(&headword, &reading),
+                    furigana_html: None,
                     glossary: vec![def_obj],
                     frequencies: vec![],
                     pitch_accents: vec![],
@@ -1775,23 +2897,45 @@ pub async fn lookup_handler(
         }
     }
 
-    // Get kanji results separately
-    let kanji_results = state
+    // Frequencies imported into the structured table. Dictionaries imported before that table
+    // existed still surface through the `Frequency:` rows handled above.
+    let result_headwords: Vec<String> = map
+        .iter()
+        .map(|agg| agg.headword.clone())
+        .chain(flat_results.iter().map(|res| res.headword.clone()))
+        .collect();
+    let structured_freqs = state
         .lookup
-        .search_kanji(&state.app, &params.text, cursor_idx);
+        .search_frequencies(&state.app, &result_headwords);
+    let structured_pitches = state.lookup.search_pitches(&state.app, &result_headwords);
+
+    // Get kanji results separately
+    let kanji_results = state.lookup.search_kanji(&state.app, scan_text, 0);
 
     if should_group {
-        let final_results: Vec<ApiGroupedResult> = map
+        let mut final_results: Vec<ApiGroupedResult> = map
             .into_iter()
             .map(|mut agg| {
                 // Attach frequencies if they exist for this word
                 if let Some(freqs) = freq_map.get(&(agg.headword.clone(), agg.reading.clone())) {
                     agg.frequencies.extend(freqs.clone());
                 }
+                agg.frequencies.extend(structured_frequencies_for(
+                    &structured_freqs,
+                    &dict_meta,
+                    &agg.headword,
+                    &agg.reading,
+                ));
                 // Attach pitch accents if they exist for this word
                 if let Some(pitches) = pitch_map.get(&(agg.headword.clone(), agg.reading.clone())) {
                     agg.pitch_accents.extend(pitches.clone());
                 }
+                agg.pitch_accents.extend(structured_pitches_for(
+                    &structured_pitches,
+                    &dict_meta,
+                    &agg.headword,
+                    &agg.reading,
+                ));
                 // Attach IPA if they exist for this word
                 if let Some(ipas) = ipa_map.get(&(agg.headword.clone(), agg.reading.clone())) {
                     agg.ipa.extend(ipas.clone());
@@ -1801,6 +2945,7 @@ pub async fn lookup_handler(
                     headword: agg.headword,
                     reading: agg.reading,
                     furigana: agg.furigana,
+                    furigana_html: None,
                     glossary: agg.glossary,
                     frequencies: agg.frequencies,
                     pitch_accents: agg.pitch_accents,
@@ -1829,9 +2974,20 @@ pub async fn lookup_handler(
             })
             .collect();
 
+        let reason = (final_results.is_empty() && kanji_results.is_empty())
+            .then(|| LookupEmptyReason::for_state(&state.app));
+        let truncated = final_results.len() > limit;
+        final_results.truncate(limit);
+
+        if params.render == LookupRender::Html {
+            attach_furigana_html(&mut final_results);
+        }
+
         Ok(Json(ApiLookupResponse {
             terms: final_results,
             kanji: kanji_results,
+            reason,
+            truncated,
         }))
     } else {
         // Iterate through results and attach frequencies to ALL of them.
@@ -1839,21 +2995,110 @@ pub async fn lookup_handler(
             if let Some(freqs) = freq_map.get(&(res.headword.clone(), res.reading.clone())) {
                 res.frequencies.extend(freqs.clone());
             }
+            res.frequencies.extend(structured_frequencies_for(
+                &structured_freqs,
+                &dict_meta,
+                &res.headword,
+                &res.reading,
+            ));
             if let Some(pitches) = pitch_map.get(&(res.headword.clone(), res.reading.clone())) {
                 res.pitch_accents.extend(pitches.clone());
             }
+            res.pitch_accents.extend(structured_pitches_for(
+                &structured_pitches,
+                &dict_meta,
+                &res.headword,
+                &res.reading,
+            ));
             if let Some(ipas) = ipa_map.get(&(res.headword.clone(), res.reading.clone())) {
                 res.ipa.extend(ipas.clone());
             }
         }
 
+        let reason = (flat_results.is_empty() && kanji_results.is_empty())
+            .then(|| LookupEmptyReason::for_state(&state.app));
+        let truncated = flat_results.len() > limit;
+        flat_results.truncate(limit);
+
+        if params.render == LookupRender::Html {
+            attach_furigana_html(&mut flat_results);
+        }
+
         Ok(Json(ApiLookupResponse {
             terms: flat_results,
             kanji: kanji_results,
+            reason,
+            truncated,
         }))
     }
 }
 
+/// Picks the structured frequencies that apply to a headword/reading pair. A frequency without a
+/// reading applies to every reading of its headword.
+fn structured_frequencies_for(
+    freqs: &[TermFrequency],
+    dict_meta: &HashMap<DictionaryId, (String, Option<String>)>,
+    headword: &str,
+    reading: &str,
+) -> Vec<ApiFrequency> {
+    freqs
+        .iter()
+        .filter(|freq| freq.term == headword)
+        .filter(|freq| match freq.reading.as_deref() {
+            None | Some("") => true,
+            Some(r) => r == reading || (reading.is_empty() && r == headword),
+        })
+        .map(|freq| ApiFrequency {
+            dictionary_name: dict_meta
+                .get(&freq.dictionary_id)
+                .map(|(name, _)| name.clone())
+                .unwrap_or("Unknown".to_string()),
+            value: freq.value.clone(),
+        })
+        .collect()
+}
+
+/// Groups the structured pitch rows for a headword/reading pair into one `ApiPitchAccent` per
+/// dictionary.
+fn structured_pitches_for(
+    pitches: &[TermPitch],
+    dict_meta: &HashMap<DictionaryId, (String, Option<String>)>,
+    headword: &str,
+    reading: &str,
+) -> Vec<ApiPitchAccent> {
+    let mut grouped: Vec<(DictionaryId, ApiPitchAccent)> = Vec::new();
+    for pitch in pitches.iter().filter(|pitch| {
+        pitch.term == headword
+            && (pitch.reading == reading || (reading.is_empty() && pitch.reading == headword))
+    }) {
+        let info = ApiPitchInfo {
+            position: pitch.position,
+            pattern: pitch.pattern.clone(),
+            nasal: pitch.nasal.clone(),
+            devoice: pitch.devoice.clone(),
+            tags: pitch.tags.clone(),
+        };
+        match grouped
+            .iter_mut()
+            .find(|(id, _)| *id == pitch.dictionary_id)
+        {
+            Some((_, accent)) => accent.pitches.push(info),
+            None => grouped.push((
+                pitch.dictionary_id,
+                ApiPitchAccent {
+                    dictionary_name: dict_meta
+                        .get(&pitch.dictionary_id)
+                        .map(|(name, _)| name.clone())
+                        .unwrap_or("Unknown".to_string()),
+                    reading: pitch.reading.clone(),
+                    pitches: vec![info],
+                },
+            )),
+        }
+    }
+    grouped.into_iter().map(|(_, accent)| accent).collect()
+}
+
 fn calculate_furigana(headword: &str, reading: &str) -> Vec<(String, String)> {
     if reading.is_empty() || headword == reading {
         return vec![(headword.to_string(), String::new())];
@@ -1888,13 +3133,157 @@ fn calculate_furigana(headword: &str, reading: &str) -> Vec<(String, String)> {
     parts
 }
 
+/// Returns the slice of `text` a lookup should scan: at most [`MAX_LOOKUP_SCAN_CHARS`] characters
+/// starting at byte offset `index` (snapped back to a char boundary). Returns `None` when `index`
+/// lies past the end of `text`.
+fn lookup_scan_window(text: &str, index: usize) -> Option<&str> {
+    if index > text.len() {
+        return None;
+    }
+    let mut start = index;
+    while start > 0 && !text.is_char_boundary(start) {
+        start -= 1;
+    }
+    let rest = &text[start..];
+    let end = rest
+        .char_indices()
+        .nth(MAX_LOOKUP_SCAN_CHARS)
+        .map(|(i, _)| i)
+        .unwrap_or(rest.len());
+    Some(&rest[..end])
+}
+
+fn attach_furigana_html(results: &mut [ApiGroupedResult]) {
+    for res in results {
+        res.furigana_html = Some(render_furigana_html(&res.furigana));
+    }
+}
+
+/// Renders furigana segments as ruby markup, e.g. `<ruby>漢字<rt>かんじ</rt></ruby>`.
+/// Segments without a reading are emitted as plain (escaped) text.
+fn render_furigana_html(segments: &[(String, String)]) -> String {
+    let mut html = String::new();
+    for (text, reading) in segments {
+        if reading.is_empty() {
+            html.push_str(&escape_html(text));
+        } else {
+            html.push_str("<ruby>");
+            html.push_str(&escape_html(text));
+            html.push_str("<rt>");
+            html.push_str(&escape_html(reading));
+            html.push_str("</rt></ruby>");
+        }
+    }
+    html
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[derive(Serialize)]
+pub struct LanguageInfo {
+    pub name: &'static str,
+    pub iso: &'static str,
+    pub iso639_3: &'static str,
+    /// False when the language only has the empty placeholder deinflector.
+    pub has_deinflector: bool,
+    pub dictionary_url: &'static str,
+}
+
+pub async fn languages_handler(State(state): State<ServerState>) -> Json<Vec<LanguageInfo>> {
+    Json(
+        DictionaryLanguage::iter()
+            .map(|language| {
+                let summary = get_audio_language_summary(language);
+                LanguageInfo {
+                    name: language.as_str(),
+                    iso: summary.iso,
+                    iso639_3: summary.iso639_3,
+                    has_deinflector: state.lookup.has_deinflector(language.deinflect_language()),
+                    dictionary_url: dictionary_url(language),
+                }
+            })
+            .collect(),
+    )
+}
+
+pub async fn version_handler() -> Json<Value> {
+    Json(json!({
+        "name": env!("CARGO_PKG_NAME"),
+        "version": env!("CARGO_PKG_VERSION"),
+    }))
+}
+
 pub async fn list_dictionaries_handler(State(state): State<ServerState>) -> Json<Value> {
     let dicts = state.app.dictionaries.read().expect("lock");
     let mut list: Vec<_> = dicts.values().cloned().collect();
     list.sort_by_key(|d| d.priority);
-    Json(
-        json!({ "dictionaries": list, "status": if state.app.is_loading() { "loading" } else { "ready" } }),
-    )
+    let mut body = json!({ "dictionaries": list, "status": if state.app.is_loading() { "loading" } else { "ready" } });
+    if state.app.is_import_running() {
+        body["import_progress"] = json!(state.app.import_progress());
+    }
+    Json(body)
+}
+
+/// Where first-run dictionary setup stands, so the UI can tell "nothing installed yet" apart
+/// from "install in progress".
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Readiness {
+    /// No dictionaries are installed and no install is running.
+    NotStarted,
+    /// A dictionary is downloading or importing; lookups answer `503 loading` meanwhile.
+    Loading,
+    /// At least one dictionary is installed and lookups are served.
+    Ready,
+}
+
+pub fn readiness(app: &AppState) -> Readiness {
+    if app.is_loading() || app.is_import_running() {
+        Readiness::Loading
+    } else if app.dictionaries.read().expect("lock").is_empty() {
+        Readiness::NotStarted
+    } else {
+        Readiness::Ready
+    }
+}
+
+pub async fn status_handler(State(state): State<ServerState>) -> Json<Value> {
+    let readiness = readiness(&state.app);
+    let mut body = json!({
+        "status": readiness,
+        "dictionaries": state.app.dictionaries.read().expect("lock").len(),
+    });
+    if readiness == Readiness::Loading {
+        body["import_progress"] = json!(state.app.import_progress());
+    }
+    // Installs requested this early wait for the guard before they start importing.
+    if state.app.is_import_startup_guard_active() {
+        body["startup_guard_remaining_secs"] =
+            json!(state.app.import_startup_guard_remaining_secs());
+    }
+    Json(body)
+}
+
+pub async fn cancel_import_handler(State(state): State<ServerState>) -> Json<Value> {
+    if !state.app.is_import_running() {
+        return Json(json!({ "status": "ok", "message": "No import in progress" }));
+    }
+    info!("🛑 [Import API] Cancellation requested");
+    state.app.request_import_cancel();
+    Json(json!({ "status": "ok", "message": "Cancellation requested" }))
 }
 
 pub async fn import_handler(
@@ -1999,3 +3388,1232 @@ pub async fn dict_media_handler(
         Err(_) => (StatusCode::NOT_FOUND, "Not found").into_response(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{TcpListener, TcpStream},
+    };
+
+    use super::*;
+    use crate::test_support::{test_data_dir, test_server_state};
+
+    async fn read_request_head(stream: &mut TcpStream) -> String {
+        let mut head = Vec::new();
+        let mut buf = [0_u8; 1024];
+        while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+            let n = stream.read(&mut buf).await.expect("read request");
+            if n == 0 {
+                break;
+            }
+            head.extend_from_slice(&buf[..n]);
+        }
+        String::from_utf8_lossy(&head).to_ascii_lowercase()
+    }
+
+    #[tokio::test]
+    async fn resumes_partial_download_with_range_header() {
+        let body: Vec<u8> = (0..4096_u32).map(|i| (i % 251) as u8).collect();
+        let split = body.len() / 2;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        let seen_ranges: Arc<Mutex<Vec<(Option<String>, Option<String>)>>> = Arc::default();
+
+        let server_body = body.clone();
+        let server_ranges = seen_ranges.clone();
+        tokio::spawn(async move {
+            for attempt in 0..2 {
+                let (mut stream, _) = listener.accept().await.expect("accept");
+                let head = read_request_head(&mut stream).await;
+                server_ranges.lock().expect("lock").push((
+                    header_value(&head, "range"),
+                    header_value(&head, "if-range"),
+                ));
+
+                if attempt == 0 {
+                    // Advertise the full body but drop the connection halfway through.
+                    let header = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nETag: \"v1\"\r\n\r\n",
+                        server_body.len()
+                    );
+                    stream.write_all(header.as_bytes()).await.expect("write");
+                    stream
+                        .write_all(&server_body[..split])
+                        .await
+                        .expect("write");
+                } else {
+                    let header = format!(
+                        "HTTP/1.1 206 Partial Content\r\nContent-Length: {}\r\nContent-Range: bytes {split}-{}/{}\r\nETag: \"v1\"\r\nConnection: close\r\n\r\n",
+                        server_body.len() - split,
+                        server_body.len() - 1,
+                        server_body.len()
+                    );
+                    stream.write_all(header.as_bytes()).await.expect("write");
+                    stream
+                        .write_all(&server_body[split..])
+                        .await
+                        .expect("write");
+                }
+                stream.shutdown().await.ok();
+            }
+        });
+
+        let dir = test_data_dir("resume-download");
+        let dest = dir.join("downloads").join("test.zip.part");
+        let url = format!("http://{addr}/dict.zip");
+
+        download_resumable(&Client::new(), &url, &dest, MAX_DOWNLOAD_BYTES)
+            .await
+            .expect("download should resume and complete");
+
+        let downloaded = tokio::fs::read(&dest).await.expect("read download");
+        assert_eq!(downloaded, body);
+
+        let ranges = seen_ranges.lock().expect("lock").clone();
+        assert_eq!(ranges.len(), 2);
+        assert_eq!(ranges[0], (None, None));
+        assert_eq!(
+            ranges[1],
+            (Some(format!("bytes={split}-")), Some("\"v1\"".to_string()))
+        );
+
+        let _ = tokio::fs::remove_dir_all(dir).await;
+    }
+
+    fn header_value(head: &str, name: &str) -> Option<String> {
+        head.lines()
+            .find_map(|line| line.strip_prefix(&format!("{name}: ")))
+            .map(|value| value.trim().to_string())
+    }
+
+    /// Serves `body` with `etag` once, answering 200 whatever the request's range says.
+    async fn serve_full_body_once(
+        body: Vec<u8>,
+        etag: &'static str,
+    ) -> (String, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.expect("accept");
+            let head = read_request_head(&mut stream).await;
+            let header = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nETag: {etag}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            stream.write_all(header.as_bytes()).await.expect("write");
+            stream.write_all(&body).await.expect("write");
+            stream.shutdown().await.ok();
+            head
+        });
+        (format!("http://{addr}/dict.zip"), server)
+    }
+
+    #[tokio::test]
+    async fn changed_archive_restarts_download_from_zero() {
+        let body = b"fresh archive bytes".to_vec();
+        let (url, server) = serve_full_body_once(body.clone(), "\"v2\"").await;
+
+        let dir = test_data_dir("resume-changed-archive");
+        let dest = dir.join("dict_downloads").join("test.zip.part");
+        tokio::fs::create_dir_all(dest.parent().expect("parent"))
+            .await
+            .expect("create dir");
+        tokio::fs::write(&dest, b"stale bytes from v1")
+            .await
+            .expect("write partial");
+        tokio::fs::write(validator_path(&dest), "\"v1\"")
+            .await
+            .expect("write validator");
+
+        download_resumable(&Client::new(), &url, &dest, MAX_DOWNLOAD_BYTES)
+            .await
+            .expect("download should restart and complete");
+
+        let head = server.await.expect("server");
+        assert_eq!(header_value(&head, "if-range").as_deref(), Some("\"v1\""));
+        assert_eq!(tokio::fs::read(&dest).await.expect("read download"), body);
+        assert_eq!(
+            tokio::fs::read_to_string(validator_path(&dest))
+                .await
+                .expect("read validator"),
+            "\"v2\""
+        );
+
+        let _ = tokio::fs::remove_dir_all(dir).await;
+    }
+
+    #[tokio::test]
+    async fn partial_without_validator_is_not_resumed() {
+        let body = b"complete archive".to_vec();
+        let (url, server) = serve_full_body_once(body.clone(), "\"v1\"").await;
+
+        let dir = test_data_dir("resume-no-validator");
+        let dest = dir.join("dict_downloads").join("test.zip.part");
+        tokio::fs::create_dir_all(dest.parent().expect("parent"))
+            .await
+            .expect("create dir");
+        tokio::fs::write(&dest, b"left over")
+            .await
+            .expect("write partial");
+
+        download_resumable(&Client::new(), &url, &dest, MAX_DOWNLOAD_BYTES)
+            .await
+            .expect("download should complete");
+
+        let head = server.await.expect("server");
+        assert_eq!(header_value(&head, "range"), None);
+        assert_eq!(tokio::fs::read(&dest).await.expect("read download"), body);
+
+        let _ = tokio::fs::remove_dir_all(dir).await;
+    }
+
+    #[test]
+    fn partial_downloads_are_private_and_claimed_once() {
+        let dir = test_data_dir("partial-download-path");
+        let state = AppState::new(dir.clone());
+
+        let path = partial_download_path(
+            &state.data_dir,
+            DictionaryLanguage::Japanese,
+            "https://a/x.zip",
+        );
+        assert!(path.starts_with(dir.join("dict_downloads")));
+        assert_ne!(
+            path,
+            partial_download_path(
+                &state.data_dir,
+                DictionaryLanguage::Japanese,
+                "https://b/x.zip"
+            )
+        );
+
+        let claim = state.claim_download(&path).expect("first claim");
+        assert!(state.claim_download(&path).is_none());
+        drop(claim);
+        assert!(state.claim_download(&path).is_some());
+
+        drop(state);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn renders_ruby_html_for_mixed_kanji_kana_headword() {
+        let furigana = calculate_furigana("食べる", "たべる");
+        assert_eq!(
+            render_furigana_html(&furigana),
+            "<ruby>食<rt>た</rt></ruby>べる"
+        );
+
+        let furigana = calculate_furigana("お茶", "おちゃ");
+        assert_eq!(
+            render_furigana_html(&furigana),
+            "お<ruby>茶<rt>ちゃ</rt></ruby>"
+        );
+    }
+
+    #[test]
+    fn escapes_furigana_html_segments() {
+        let segments = vec![
+            ("<b>".to_string(), String::new()),
+            ("漢".to_string(), "\"&".to_string()),
+        ];
+        assert_eq!(
+            render_furigana_html(&segments),
+            "&lt;b&gt;<ruby>漢<rt>&quot;&amp;</rt></ruby>"
+        );
+    }
+
+    #[test]
+    fn clamps_over_long_lookup_text() {
+        let text = "猫".repeat(10_000);
+        let window = lookup_scan_window(&text, 0).expect("index 0 is valid");
+        assert_eq!(window.chars().count(), MAX_LOOKUP_SCAN_CHARS);
+
+        // A cursor in the middle of a multi-byte char snaps back to its start.
+        let window = lookup_scan_window("あ猫", 4).expect("index inside text is valid");
+        assert_eq!(window, "猫");
+
+        assert_eq!(lookup_scan_window("猫", 3), Some(""));
+    }
+
+    #[tokio::test]
+    async fn offline_mode_skips_audio_lookups() {
+        let dir = test_data_dir("offline-audio");
+        let state = test_server_state(&dir);
+        state.app.set_offline(true);
+
+        let params = AudioParams {
+            term: "猫".to_string(),
+            reading: Some("ねこ".to_string()),
+            source: AudioSource::Jisho,
+            language: Some(DictionaryLanguage::Japanese),
+            deinflect: false,
+        };
+        let Json(response) = audio_handler(State(state), Query(params))
+            .await
+            .expect("offline audio lookup should succeed");
+        assert!(response.url.is_none());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn offline_mode_rejects_dictionary_downloads() {
+        let dir = test_data_dir("offline-download");
+        let app = AppState::new(dir.clone());
+        app.set_offline(true);
+
+        let err = download_dictionary_bytes(&app, DictionaryLanguage::Japanese)
+            .await
+            .expect_err("offline download should fail");
+        assert!(err.contains("offline"));
+        // Nothing was staged, so no request was attempted.
+        let lang = DictionaryLanguage::Japanese;
+        let staged = partial_download_path(&app.data_dir, lang, dictionary_url(lang));
+        assert!(!staged.parent().expect("staging dir").exists());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn install_defaults_fails_when_downloads_are_disallowed() {
+        let dir = test_data_dir("downloads-disallowed");
+        let state = test_server_state(&dir);
+        state.app.set_dict_downloads_allowed(false);
+
+        let Json(response) = install_defaults_handler(State(state.clone()), None).await;
+        assert_eq!(response["status"], "error");
+        let message = response["message"].as_str().expect("error message");
+        assert!(message.contains("Upload a dictionary zip"));
+        let lang = DictionaryLanguage::Japanese;
+        let staged = partial_download_path(&state.app.data_dir, lang, dictionary_url(lang));
+        assert!(!staged.parent().expect("staging dir").exists());
+        assert!(!state.app.is_loading());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn detects_language_from_script() {
+        assert!(matches!(
+            detect_language("안녕하세요"),
+            Some(DictionaryLanguage::Korean)
+        ));
+        assert!(matches!(
+            detect_language("Привет, мир"),
+            Some(DictionaryLanguage::Russian)
+        ));
+        assert!(matches!(
+            detect_language("Привіт"),
+            Some(DictionaryLanguage::Ukrainian)
+        ));
+        assert!(matches!(
+            detect_language("hello world"),
+            Some(DictionaryLanguage::English)
+        ));
+        assert!(matches!(
+            detect_language("猫が好き"),
+            Some(DictionaryLanguage::Japanese)
+        ));
+        assert!(matches!(
+            detect_language("我们说中文"),
+            Some(DictionaryLanguage::Chinese)
+        ));
+        assert!(detect_language("123 !?").is_none());
+    }
+
+    #[test]
+    fn explicit_language_beats_detection() {
+        let dir = test_data_dir("resolve-language");
+        let app = AppState::new(dir.clone());
+
+        assert!(matches!(
+            resolve_language(&app, None, Some("안녕")),
+            DictionaryLanguage::Korean
+        ));
+        assert!(matches!(
+            resolve_language(&app, Some(DictionaryLanguage::Japanese), Some("안녕")),
+            DictionaryLanguage::Japanese
+        ));
+        store_preferred_language(&app, DictionaryLanguage::Chinese);
+        assert!(matches!(
+            resolve_language(&app, None, Some("hello")),
+            DictionaryLanguage::Chinese
+        ));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn stored_preference_replaces_japanese_default() {
+        let dir = test_data_dir("resolve-language-default");
+        let app = AppState::new(dir.clone());
+
+        // Audio and install requests carry no text to detect a language from.
+        assert!(matches!(
+            resolve_language(&app, None, None),
+            DictionaryLanguage::Japanese
+        ));
+        store_preferred_language(&app, DictionaryLanguage::Spanish);
+        assert!(matches!(
+            resolve_language(&app, None, None),
+            DictionaryLanguage::Spanish
+        ));
+        assert!(matches!(
+            resolve_language(&app, Some(DictionaryLanguage::Korean), None),
+            DictionaryLanguage::Korean
+        ));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn audio_handlers_use_the_stored_language() {
+        let dir = test_data_dir("audio-stored-language");
+        let state = test_server_state(&dir);
+        store_preferred_language(&state.app, DictionaryLanguage::Spanish);
+        let fetch = |_: AudioSource, _: String, _: String, language: DictionaryLanguage| async move {
+            Ok::<_, anyhow::Error>(Some(format!("https://audio.test/{language}")))
+        };
+
+        let Json(single) = audio_with(
+            &state,
+            AudioParams {
+                term: "hola".to_string(),
+                reading: None,
+                source: AudioSource::LinguaLibre,
+                language: None,
+                deinflect: false,
+            },
+            fetch,
+        )
+        .await
+        .ok()
+        .expect("audio lookup should succeed");
+        assert_eq!(single.url.as_deref(), Some("https://audio.test/spanish"));
+
+        let Json(any) = audio_any_with(
+            &state,
+            AnyAudioParams {
+                term: "hola".to_string(),
+                reading: None,
+                sources: "wiktionary".to_string(),
+                language: None,
+            },
+            fetch,
+        )
+        .await
+        .ok()
+        .expect("audio lookup should succeed");
+        assert_eq!(any.url.as_deref(), Some("https://audio.test/spanish"));
+
+        drop(state);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn install_defaults_uses_the_stored_language() {
+        let dir = test_data_dir("install-defaults-stored-language");
+        let state = AppState::new(dir.clone());
+        store_preferred_language(&state, DictionaryLanguage::Korean);
+
+        let requested = Arc::new(Mutex::new(None));
+        let Json(body) = install_defaults_with(&state, None, {
+            let requested = requested.clone();
+            move |app, language| {
+                *requested.lock().expect("lock") = Some(language);
+                fake_fetch(app, language)
+            }
+        })
+        .await;
+        assert_eq!(body["status"], "ok");
+        assert_eq!(
+            *requested.lock().expect("lock"),
+            Some(DictionaryLanguage::Korean)
+        );
+        assert_eq!(
+            installed_language_dictionary(&state, DictionaryLanguage::Korean).as_deref(),
+            Some("Test korean")
+        );
+
+        drop(state);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn languages_report_deinflector_support() {
+        let dir = test_data_dir("languages");
+        let state = test_server_state(&dir);
+
+        let Json(languages) = languages_handler(State(state)).await;
+        assert_eq!(languages.len(), DictionaryLanguage::iter().count());
+        let japanese = languages
+            .iter()
+            .find(|info| info.name == "japanese")
+            .expect("japanese listed");
+        assert!(japanese.has_deinflector);
+        assert_eq!(japanese.iso, "ja");
+        let russian = languages
+            .iter()
+            .find(|info| info.name == "russian")
+            .expect("russian listed");
+        assert!(!russian.has_deinflector);
+        assert!(!russian.dictionary_url.is_empty());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn language_header_applies_without_query_param() {
+        let dir = test_data_dir("language-header");
+        let app = AppState::new(dir.clone());
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            LANGUAGE_HEADER,
+            "Korean".parse().expect("valid header value"),
+        );
+
+        assert!(matches!(
+            lookup_language(&app, None, &headers, "猫"),
+            DictionaryLanguage::Korean
+        ));
+        // The query param still wins over the header, and the header over the stored preference.
+        assert!(matches!(
+            lookup_language(&app, Some(DictionaryLanguage::Chinese), &headers, "猫"),
+            DictionaryLanguage::Chinese
+        ));
+        store_preferred_language(&app, DictionaryLanguage::English);
+        assert!(matches!(
+            lookup_language(&app, None, &headers, "猫"),
+            DictionaryLanguage::Korean
+        ));
+        assert!(matches!(
+            lookup_language(&app, None, &HeaderMap::new(), "猫"),
+            DictionaryLanguage::English
+        ));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    fn lookup_params(text: &str) -> LookupParams {
+        LookupParams {
+            text: text.to_string(),
+            index: None,
+            group: None,
+            language: Some(DictionaryLanguage::Japanese),
+            render: LookupRender::default(),
+            limit: None,
+            format: LookupFormat::default(),
+            by: LookupBy::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn empty_lookup_reports_reason() {
+        let dir = test_data_dir("lookup-empty-reason");
+        let state = test_server_state(&dir);
+
+        let Json(response) = lookup_handler(
+            State(state.clone()),
+            HeaderMap::new(),
+            Query(lookup_params("猫")),
+        )
+        .await
+        .ok()
+        .expect("lookup should succeed");
+        assert!(response.terms.is_empty());
+        assert_eq!(response.reason, Some(LookupEmptyReason::EmptyDictionaries));
+
+        let bytes = build_dictionary_zip_with("Cats", r#"[["猫","ねこ","",null,1,["cat"],0,""]]"#);
+        import::import_zip(&state.app, &bytes).expect("import should succeed");
+
+        let Json(response) = lookup_handler(
+            State(state.clone()),
+            HeaderMap::new(),
+            Query(lookup_params("xyz")),
+        )
+        .await
+        .ok()
+        .expect("lookup should succeed");
+        assert!(response.terms.is_empty());
+        assert_eq!(response.reason, Some(LookupEmptyReason::NoMatch));
+
+        let Json(response) = lookup_handler(
+            State(state.clone()),
+            HeaderMap::new(),
+            Query(lookup_params("猫")),
+        )
+        .await
+        .ok()
+        .expect("lookup should succeed");
+        assert!(!response.terms.is_empty());
+        assert_eq!(response.reason, None);
+
+        state.app.set_loading(true);
+        let (status, Json(body)) =
+            lookup_handler(State(state), HeaderMap::new(), Query(lookup_params("猫")))
+                .await
+                .err()
+                .expect("lookup should report loading");
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["reason"], "loading");
+        assert_eq!(body["terms"], json!([]));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn status_moves_from_not_started_through_loading_to_ready() {
+        use std::time::Duration;
+
+        async fn wait_for_status(state: &ServerState, want: &str) -> Value {
+            tokio::time::timeout(Duration::from_secs(5), async {
+                loop {
+                    let Json(body) = status_handler(State(state.clone())).await;
+                    if body["status"] == want {
+                        return body;
+                    }
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+            })
+            .await
+            .unwrap_or_else(|_| panic!("/status never reported {want}"))
+        }
+
+        let dir = test_data_dir("status-readiness");
+        let state = test_server_state(&dir);
+
+        let Json(body) = status_handler(State(state.clone())).await;
+        assert_eq!(body["status"], "not_started");
+
+        // The first-run install gets its archive from a local fixture, held back until the test
+        // has seen the install in progress.
+        let (release, held) = tokio::sync::oneshot::channel::<()>();
+        let install = tokio::spawn({
+            let app = state.app.clone();
+            async move {
+                install_defaults_with(&app, None, |_, _| async move {
+                    let _ = held.await;
+                    Ok(build_dictionary_zip("Status Dict"))
+                })
+                .await
+            }
+        });
+
+        let body = wait_for_status(&state, "loading").await;
+        assert!(body["import_progress"].is_object());
+        assert_eq!(body["dictionaries"], 0);
+
+        release
+            .send(())
+            .expect("install is waiting for the fixture");
+        let body = wait_for_status(&state, "ready").await;
+        assert_eq!(body["dictionaries"], 1);
+        let Json(installed) = install.await.expect("install task");
+        assert_eq!(installed["status"], "ok");
+
+        drop(state);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn lookup_limit_caps_and_flags_results() {
+        let dir = test_data_dir("lookup-limit");
+        let state = test_server_state(&dir);
+        let bytes = build_dictionary_zip_with(
+            "Readings",
+            r#"[["猫","ねこ","",null,1,["cat"],0,""],["猫","びょう","",null,1,["cat (on)"],0,""],["猫","みょう","",null,1,["cat (rare)"],0,""]]"#,
+        );
+        import::import_zip(&state.app, &bytes).expect("import should succeed");
+
+        let Json(response) = lookup_handler(
+            State(state.clone()),
+            HeaderMap::new(),
+            Query(lookup_params("猫")),
+        )
+        .await
+        .ok()
+        .expect("lookup should succeed");
+        assert_eq!(response.terms.len(), 3);
+        assert!(!response.truncated);
+
+        let params = LookupParams {
+            limit: Some(2),
+            format: LookupFormat::default(),
+            by: LookupBy::default(),
+            ..lookup_params("猫")
+        };
+        let Json(response) = lookup_handler(State(state.clone()), HeaderMap::new(), Query(params))
+            .await
+            .ok()
+            .expect("lookup should succeed");
+        assert!(response.truncated);
+        // Equally ranked readings are ordered by reading, so the same two survive every time.
+        let readings: Vec<&str> = response.terms.iter().map(|t| t.reading.as_str()).collect();
+        assert_eq!(readings, ["ねこ", "びょう"]);
+
+        let params = LookupParams {
+            limit: Some(0),
+            format: LookupFormat::default(),
+            by: LookupBy::default(),
+            ..lookup_params("猫")
+        };
+        let err = lookup_handler(State(state), HeaderMap::new(), Query(params))
+            .await
+            .err()
+            .expect("limit=0 should be rejected");
+        assert_eq!(err.0, StatusCode::BAD_REQUEST);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn rejects_out_of_range_lookup_index() {
+        let dir = test_data_dir("lookup-index");
+        let state = test_server_state(&dir);
+
+        let params = LookupParams {
+            text: "猫".to_string(),
+            index: Some(usize::MAX),
+            group: None,
+            language: Some(DictionaryLanguage::Japanese),
+            render: LookupRender::default(),
+            limit: None,
+            format: LookupFormat::default(),
+            by: LookupBy::default(),
+        };
+        let err = lookup_handler(State(state), HeaderMap::new(), Query(params))
+            .await
+            .err()
+            .expect("out-of-range index should be rejected");
+        assert_eq!(err.0, StatusCode::BAD_REQUEST);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    fn build_dictionary_zip(title: &str) -> Vec<u8> {
+        build_dictionary_zip_with(title, r#"[["猫","ねこ","",null,1,["cat"],0,""]]"#)
+    }
+
+    fn build_dictionary_zip_with(title: &str, term_bank: &str) -> Vec<u8> {
+        use std::io::Write;
+
+        use zip::{CompressionMethod, ZipWriter, write::SimpleFileOptions};
+
+        let mut bytes = Vec::new();
+        {
+            let mut zip = ZipWriter::new(std::io::Cursor::new(&mut bytes));
+            let opts = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+            zip.start_file("index.json", opts).expect("start index");
+            zip.write_all(format!(r#"{{"format":3,"title":"{title}","revision":"1"}}"#).as_bytes())
+                .expect("write index");
+            zip.start_file("term_bank_1.json", opts)
+                .expect("start term bank");
+            zip.write_all(term_bank.as_bytes())
+                .expect("write term bank");
+            zip.finish().expect("finish zip");
+        }
+        bytes
+    }
+
+    async fn fake_fetch(_app: AppState, language: DictionaryLanguage) -> Result<Vec<u8>, String> {
+        Ok(build_dictionary_zip(&format!("Test {language}")))
+    }
+
+    #[tokio::test]
+    async fn batch_install_skips_languages_already_present() {
+        let dir = test_data_dir("install-languages");
+        let state = AppState::new(dir.clone());
+
+        let first =
+            install_languages_with(&state, &[DictionaryLanguage::Japanese], fake_fetch).await;
+        assert_eq!(first[0].status, LanguageInstallStatus::Installed);
+
+        let reports = install_languages_with(
+            &state,
+            &[DictionaryLanguage::Japanese, DictionaryLanguage::Korean],
+            fake_fetch,
+        )
+        .await;
+
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0].language.as_str(), "japanese");
+        assert_eq!(reports[0].status, LanguageInstallStatus::Skipped);
+        assert_eq!(reports[1].language.as_str(), "korean");
+        assert_eq!(reports[1].status, LanguageInstallStatus::Installed);
+        assert_eq!(state.dictionaries.read().expect("lock").len(), 2);
+
+        drop(state);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn batch_install_recognizes_dictionaries_installed_before_language_metadata() {
+        let dir = test_data_dir("install-languages-legacy");
+        let state = AppState::new(dir.clone());
+        import::import_zip(&state, &build_dictionary_zip("CC-CEDICT [2025-01-01]"))
+            .expect("legacy import");
+        import::import_zip(&state, &build_dictionary_zip("kty-ko-en")).expect("legacy import");
+
+        let reports = install_languages_with(
+            &state,
+            &[DictionaryLanguage::Chinese, DictionaryLanguage::Korean],
+            |_, language| async move { Err(format!("{language} should not be downloaded")) },
+        )
+        .await;
+
+        assert!(
+            reports
+                .iter()
+                .all(|report| report.status == LanguageInstallStatus::Skipped)
+        );
+        assert_eq!(state.dictionaries.read().expect("lock").len(), 2);
+
+        drop(state);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn update_finds_source_for_dictionary_without_language_metadata() {
+        let dir = test_data_dir("update-legacy-dictionary");
+        let state = AppState::new(dir.clone());
+        import::import_zip(&state, &build_dictionary_zip("kty-ko-en")).expect("legacy import");
+        let id = dictionary_id_by_name(&state, "kty-ko-en").expect("legacy dictionary id");
+        let conn = state.pool.get().expect("conn");
+        let recorded: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM metadata WHERE key LIKE 'installed_language:%'",
+                [],
+                |row| row.get(0),
+            )
+            .expect("metadata count");
+        assert_eq!(recorded, 0);
+        drop(conn);
+
+        assert_eq!(
+            language_for_dictionary(&state, id).map(|language| language.as_str()),
+            Some("korean")
+        );
+
+        update_language_bytes(
+            state.clone(),
+            DictionaryLanguage::Korean,
+            id,
+            build_dictionary_zip("kty-ko-en"),
+        )
+        .await
+        .expect("update should succeed");
+        let new_id = dictionary_id_by_name(&state, "kty-ko-en").expect("updated dictionary id");
+        assert_ne!(new_id, id);
+        assert_eq!(
+            installed_language_dictionary(&state, DictionaryLanguage::Korean).as_deref(),
+            Some("kty-ko-en")
+        );
+        assert_eq!(
+            language_for_dictionary(&state, new_id).map(|language| language.as_str()),
+            Some("korean")
+        );
+
+        drop(state);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn install_records_the_imported_dictionary() {
+        let dir = test_data_dir("install-languages-record");
+        let state = AppState::new(dir.clone());
+        import::import_zip(&state, &build_dictionary_zip("Unrelated")).expect("import");
+
+        let reports =
+            install_languages_with(&state, &[DictionaryLanguage::Korean], fake_fetch).await;
+        assert_eq!(reports[0].status, LanguageInstallStatus::Installed);
+        assert_eq!(
+            installed_language_dictionary(&state, DictionaryLanguage::Korean).as_deref(),
+            Some("Test korean")
+        );
+
+        drop(state);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn batch_install_never_exceeds_concurrent_download_limit() {
+        use std::sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        };
+
+        let dir = test_data_dir("install-languages-concurrency");
+        let state = AppState::with_max_concurrent_downloads(dir.clone(), 2);
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let reports = install_languages_with(
+            &state,
+            &[
+                DictionaryLanguage::Japanese,
+                DictionaryLanguage::Korean,
+                DictionaryLanguage::Chinese,
+                DictionaryLanguage::Spanish,
+                DictionaryLanguage::French,
+            ],
+            |app, language| {
+                let in_flight = in_flight.clone();
+                let peak = peak.clone();
+                async move {
+                    let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                    fake_fetch(app, language).await
+                }
+            },
+        )
+        .await;
+
+        assert_eq!(reports.len(), 5);
+        assert!(
+            reports
+                .iter()
+                .all(|report| report.status == LanguageInstallStatus::Installed)
+        );
+        // More downloads than slots: the limit is reached and never exceeded.
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+
+        drop(state);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn update_dictionary_download_waits_for_a_free_slot() {
+        use std::{
+            sync::atomic::{AtomicBool, Ordering},
+            time::Duration,
+        };
+
+        let dir = test_data_dir("update-waits-for-slot");
+        let state = AppState::with_max_concurrent_downloads(dir.clone(), 1);
+        install_languages_with(&state, &[DictionaryLanguage::Japanese], fake_fetch).await;
+
+        let fetched = Arc::new(AtomicBool::new(false));
+        let busy_slot = state.acquire_download_permit().await;
+        let update = update_dictionary_with(
+            &state,
+            UpdateDictionaryRequest {
+                id: None,
+                language: Some(DictionaryLanguage::Japanese),
+            },
+            {
+                let fetched = fetched.clone();
+                move |app, language| {
+                    fetched.store(true, Ordering::SeqCst);
+                    fake_fetch(app, language)
+                }
+            },
+        );
+        tokio::pin!(update);
+        assert!(
+            tokio::time::timeout(Duration::from_millis(300), &mut update)
+                .await
+                .is_err(),
+            "update must not download while every slot is taken"
+        );
+        assert!(!fetched.load(Ordering::SeqCst));
+
+        drop(busy_slot);
+        let Json(body) = tokio::time::timeout(Duration::from_secs(5), update)
+            .await
+            .expect("update should proceed once the slot frees up");
+        assert_eq!(body["status"], "ok");
+        assert!(fetched.load(Ordering::SeqCst));
+
+        drop(state);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn lookup_reads_frequencies_from_structured_store() {
+        use std::io::Write;
+
+        use zip::{CompressionMethod, ZipWriter, write::SimpleFileOptions};
+
+        let dir = test_data_dir("structured-frequencies");
+        let state = test_server_state(&dir);
+
+        let mut bytes = Vec::new();
+        {
+            let mut zip = ZipWriter::new(std::io::Cursor::new(&mut bytes));
+            let opts = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+            for (name, contents) in [
+                (
+                    "index.json",
+                    r#"{"format":3,"title":"Freq Dict","revision":"1"}"#,
+                ),
+                (
+                    "term_bank_1.json",
+                    r#"[["猫","ねこ","",null,1,["cat"],0,""]]"#,
+                ),
+                (
+                    "term_meta_bank_1.json",
+                    r#"[["猫","freq",{"reading":"ねこ","frequency":{"value":1500,"displayValue":"1500㋕"}}]]"#,
+                ),
+            ] {
+                zip.start_file(name, opts).expect("start file");
+                zip.write_all(contents.as_bytes()).expect("write file");
+            }
+            zip.finish().expect("finish zip");
+        }
+        import::import_zip(&state.app, &bytes).expect("import should succeed");
+
+        let params = LookupParams {
+            text: "猫".to_string(),
+            index: None,
+            group: None,
+            language: Some(DictionaryLanguage::Japanese),
+            render: LookupRender::default(),
+            limit: None,
+            format: LookupFormat::default(),
+            by: LookupBy::default(),
+        };
+        let Json(response) = lookup_handler(State(state), HeaderMap::new(), Query(params))
+            .await
+            .ok()
+            .expect("lookup should succeed");
+
+        let term = response.terms.first().expect("term result");
+        assert_eq!(term.headword, "猫");
+        assert_eq!(term.frequencies.len(), 1);
+        assert_eq!(term.frequencies[0].dictionary_name, "Freq Dict");
+        assert_eq!(term.frequencies[0].value, "1500㋕");
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn yomichan_format_matches_terms_find_shape() {
+        let dir = test_data_dir("yomichan-format");
+        let state = test_server_state(&dir);
+
+        let bytes =
+            build_dictionary_zip_with("Export Dict", r#"[["猫","ねこ","n","",1,["cat"],0,""]]"#);
+        import::import_zip(&state.app, &bytes).expect("import should succeed");
+
+        let mut params = lookup_params("猫");
+        params.format = LookupFormat::Yomichan;
+        let Json(body) = lookup_route_handler(State(state), HeaderMap::new(), Query(params))
+            .await
+            .ok()
+            .expect("lookup should succeed");
+
+        let mut keys: Vec<&str> = body
+            .as_object()
+            .expect("object body")
+            .keys()
+            .map(String::as_str)
+            .collect();
+        keys.sort_unstable();
+        assert_eq!(keys, ["definitions", "length"]);
+        assert_eq!(body["length"], 1);
+
+        let entry = &body["definitions"][0];
+        assert_eq!(entry["expression"], "猫");
+        assert_eq!(entry["reading"], "ねこ");
+        assert!(entry["termTags"].is_array());
+        assert_eq!(entry["definitions"][0]["dictionary"], "Export Dict");
+        assert_eq!(entry["definitions"][0]["glossary"][0], "cat");
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn reading_lookup_returns_every_headword_with_that_reading() {
+        let dir = test_data_dir("lookup-by-reading");
+        let state = test_server_state(&dir);
+
+        let bytes = build_dictionary_zip_with(
+            "Reading Dict",
+            r#"[["紙","かみ","n","",3,["paper"],0,""],["髪","かみ","n","",2,["hair"],0,""],["神","かみ","n","",1,["god"],0,""],["猫","ねこ","n","",1,["cat"],0,""]]"#,
+        );
+        import::import_zip(&state.app, &bytes).expect("import should succeed");
+
+        let mut params = lookup_params("かみ");
+        params.by = LookupBy::Reading;
+        let Json(response) = lookup_handler(State(state), HeaderMap::new(), Query(params))
+            .await
+            .ok()
+            .expect("lookup should succeed");
+
+        let mut headwords: Vec<&str> = response
+            .terms
+            .iter()
+            .map(|term| term.headword.as_str())
+            .collect();
+        headwords.sort_unstable();
+        assert_eq!(headwords, ["神", "紙", "髪"]);
+        assert!(response.terms.iter().all(|term| term.reading == "かみ"));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn lookup_builds_pitch_accents_from_structured_store() {
+        use std::io::Write;
+
+        use zip::{CompressionMethod, ZipWriter, write::SimpleFileOptions};
+
+        let dir = test_data_dir("structured-pitch");
+        let state = test_server_state(&dir);
+
+        let mut bytes = Vec::new();
+        {
+            let mut zip = ZipWriter::new(std::io::Cursor::new(&mut bytes));
+            let opts = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+            for (name, contents) in [
+                (
+                    "index.json",
+                    r#"{"format":3,"title":"Pitch Dict","revision":"1"}"#,
+                ),
+                (
+                    "term_bank_1.json",
+                    r#"[["箸","はし","",null,1,["chopsticks"],0,""]]"#,
+                ),
+                (
+                    "term_meta_bank_1.json",
+                    r#"[["箸","pitch",{"reading":"はし","pitches":[{"position":1,"devoice":[2]},{"position":"HL","tags":["n"]}]}]]"#,
+                ),
+            ] {
+                zip.start_file(name, opts).expect("start file");
+                zip.write_all(contents.as_bytes()).expect("write file");
+            }
+            zip.finish().expect("finish zip");
+        }
+        import::import_zip(&state.app, &bytes).expect("import should succeed");
+
+        let params = LookupParams {
+            text: "箸".to_string(),
+            index: None,
+            group: None,
+            language: Some(DictionaryLanguage::Japanese),
+            render: LookupRender::default(),
+            limit: None,
+            format: LookupFormat::default(),
+            by: LookupBy::default(),
+        };
+        let Json(response) = lookup_handler(State(state), HeaderMap::new(), Query(params))
+            .await
+            .ok()
+            .expect("lookup should succeed");
+
+        let term = response.terms.first().expect("term result");
+        assert_eq!(term.pitch_accents.len(), 1);
+        let accent = &term.pitch_accents[0];
+        assert_eq!(accent.dictionary_name, "Pitch Dict");
+        assert_eq!(accent.reading, "はし");
+        assert_eq!(accent.pitches.len(), 2);
+        assert_eq!(accent.pitches[0].position, 1);
+        assert_eq!(accent.pitches[0].devoice, vec![2]);
+        assert_eq!(accent.pitches[1].position, -1);
+        assert_eq!(accent.pitches[1].pattern, "HL");
+        assert_eq!(accent.pitches[1].tags, vec!["n".to_string()]);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn audio_any_uses_first_source_with_a_url() {
+        let sources = parse_audio_sources("jisho, jpod101,wiktionary").expect("valid sources");
+        assert_eq!(
+            sources,
+            vec![
+                AudioSource::Jisho,
+                AudioSource::Jpod101,
+                AudioSource::Wiktionary
+            ]
+        );
+
+        let mut tried = Vec::new();
+        let found = first_audio_url(&sources, |source| {
+            tried.push(source);
+            async move {
+                Ok(match source {
+                    AudioSource::Jisho => None,
+                    other => Some(format!("https://audio.test/{other:?}")),
+                })
+            }
+        })
+        .await;
+
+        assert_eq!(
+            found,
+            Some((
+                AudioSource::Jpod101,
+                "https://audio.test/Jpod101".to_string()
+            ))
+        );
+        assert_eq!(tried, vec![AudioSource::Jisho, AudioSource::Jpod101]);
+        assert!(parse_audio_sources("jisho,forvo").is_err());
+    }
+
+    #[tokio::test]
+    async fn audio_falls_back_to_deinflected_base_form() {
+        let dir = test_data_dir("audio-lemma");
+        let state = test_server_state(&dir);
+        let zip = build_dictionary_zip_with(
+            "Verb Dict",
+            r#"[["食べる","たべる","v1","v1",1,["to eat"],0,""]]"#,
+        );
+        import::import_zip(&state.app, &zip).expect("import should succeed");
+
+        let lemmas = audio_lemma_candidates(&state, "食べた", DictionaryLanguage::Japanese);
+        assert!(lemmas.contains(&("食べる".to_string(), "たべる".to_string())));
+
+        let mut tried = Vec::new();
+        let url = audio_with_lemma_fallback("食べた", "たべた", &lemmas, |term, reading| {
+            tried.push(term.to_string());
+            let url = (term == "食べる" && reading == "たべる")
+                .then(|| "https://audio.test/taberu.mp3".to_string());
+            async move { Ok(url) }
+        })
+        .await
+        .expect("fallback lookup");
+
+        assert_eq!(url.as_deref(), Some("https://audio.test/taberu.mp3"));
+        assert_eq!(tried.first().map(String::as_str), Some("食べた"));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn study_language_seeds_preferred_language_once() {
+        let dir = test_data_dir("study-language");
+        let app = AppState::new(dir.clone());
+
+        assert!(load_preferred_language(&app).is_none());
+        let seeded = seed_preferred_language(&app, Some("Korean"));
+        assert!(matches!(seeded, Some(DictionaryLanguage::Korean)));
+        assert!(matches!(
+            load_preferred_language(&app),
+            Some(DictionaryLanguage::Korean)
+        ));
+
+        // An existing preference wins over the configured study language.
+        assert!(seed_preferred_language(&app, Some("chinese")).is_none());
+        assert!(matches!(
+            load_preferred_language(&app),
+            Some(DictionaryLanguage::Korean)
+        ));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn parses_content_range_total() {
+        assert_eq!(parse_content_range_total("bytes 100-199/200"), Some(200));
+        assert_eq!(parse_content_range_total("bytes */4096"), Some(4096));
+        assert_eq!(parse_content_range_total("bytes 0-99/*"), None);
+    }
+}
@@ -31,6 +31,19 @@ const MAX_ZIP_ENTRY_COUNT: usize = 65536;
 const MAX_COMPRESSION_RATIO: u64 = 300;
 const MAX_TERMS_INSERTED: usize = 8_000_000;
 const SQLITE_MAX_BIND_PARAMS: usize = 900;
+const CREATE_TERM_INDEXES_SQL: &str = "CREATE INDEX IF NOT EXISTS idx_term ON terms(term);
+     CREATE INDEX IF NOT EXISTS idx_reading ON terms(reading);
+     CREATE INDEX IF NOT EXISTS idx_dict_term ON terms(dictionary_id);
+     CREATE INDEX IF NOT EXISTS idx_term_dict ON terms(term, dictionary_id);
+     CREATE INDEX IF NOT EXISTS idx_reading_dict ON terms(reading, dictionary_id);";
+pub(crate) const DELETE_DICTIONARY_SQL: [&str; 6] = [
+    "DELETE FROM terms WHERE dictionary_id = ?",
+    "DELETE FROM term_frequencies WHERE dictionary_id = ?",
+    "DELETE FROM term_pitches WHERE dictionary_id = ?",
+    "DELETE FROM kanji WHERE dictionary_id = ?",
+    "DELETE FROM kanji_meta WHERE dictionary_id = ?",
+    "DELETE FROM dictionaries WHERE id = ?",
+];
 
 fn read_limited_string<R: Read>(reader: R, byte_limit: u64, label: &str) -> Result<String> {
     let mut limited_reader = reader.take(byte_limit.saturating_add(1));
@@ -126,7 +139,7 @@ fn bump_term_count_by(terms_found: &mut usize, delta: usize) -> Result<()> {
     Ok(())
 }
 
-fn env_flag(name: &str) -> bool {
+pub(crate) fn env_flag(name: &str) -> bool {
     matches!(
         std::env::var(name)
             .unwrap_or_default()
@@ -136,7 +149,20 @@ fn env_flag(name: &str) -> bool {
     )
 }
 
-fn env_usize(name: &str) -> Option<usize> {
+/// Like `env_flag`, but `default` applies when the variable is unset or not a recognized value.
+pub(crate) fn env_flag_or(name: &str, default: bool) -> bool {
+    match std::env::var(name)
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+        .as_str()
+    {
+        "1" | "true" | "yes" | "on" => true,
+        "0" | "false" | "no" | "off" => false,
+        _ => default,
+    }
+}
+
+pub(crate) fn env_usize(name: &str) -> Option<usize> {
     std::env::var(name)
         .ok()
         .and_then(|value| value.parse::<usize>().ok())
@@ -160,9 +186,8 @@ fn safe_join_path(base: &Path, relative: &str) -> Option<PathBuf> {
     Some(out)
 }
 
-fn dict_archive_path(state: &AppState, dict_id: DictionaryId) -> PathBuf {
-    state
-        .data_dir
+pub(crate) fn dict_archive_path(data_dir: &Path, dict_id: DictionaryId) -> PathBuf {
+    data_dir
         .join("dict_archives")
         .join(format!("{}.zip", dict_id.0))
 }
@@ -877,10 +902,21 @@ fn parse_position_array(value: Option<&Value>) -> Vec<i64> {
     }
 }
 
-fn parse_pitch_meta(data_blob: &Value) -> (String, Option<String>) {
+/// One pitch accent from a `term_meta_bank` `pitch` entry. `position` is the downstep mora
+/// number; downstep-pattern strings (e.g. `"HLL"`) are kept in `pattern` with `position = -1`.
+#[derive(Debug, PartialEq)]
+struct PitchMetaEntry {
+    position: i64,
+    pattern: String,
+    nasal: Vec<i64>,
+    devoice: Vec<i64>,
+    tags: Vec<String>,
+}
+
+fn parse_pitch_meta(data_blob: &Value) -> (String, Vec<PitchMetaEntry>) {
     let obj = match data_blob.as_object() {
         Some(o) => o,
-        None => return ("Pitch:{}".to_string(), None),
+        None => return (String::new(), Vec::new()),
     };
 
     let reading = obj
@@ -902,9 +938,14 @@ fn parse_pitch_meta(data_blob: &Value) -> (String, Option<String>) {
             None => continue,
         };
 
-        let position = pitch_obj.get("position").cloned().unwrap_or(Value::Null);
-        let nasal = parse_position_array(pitch_obj.get("nasal"));
-        let devoice = parse_position_array(pitch_obj.get("devoice"));
+        let (position, pattern) = match pitch_obj.get("position") {
+            Some(Value::Number(n)) => match n.as_i64() {
+                Some(n) => (n, String::new()),
+                None => continue,
+            },
+            Some(Value::String(s)) => (-1, s.clone()),
+            _ => continue,
+        };
 
         let tags: Vec<String> = pitch_obj
             .get("tags")
@@ -916,27 +957,16 @@ fn parse_pitch_meta(data_blob: &Value) -> (String, Option<String>) {
             })
             .unwrap_or_default();
 
-        pitches.push(serde_json::json!({
-            "position": position,
-            "nasal": nasal,
-            "devoice": devoice,
-            "tags": tags
-        }));
+        pitches.push(PitchMetaEntry {
+            position,
+            pattern,
+            nasal: parse_position_array(pitch_obj.get("nasal")),
+            devoice: parse_position_array(pitch_obj.get("devoice")),
+            tags,
+        });
     }
 
-    let pitch_data = serde_json::json!({
-        "reading": reading,
-        "pitches": pitches
-    });
-
-    let content = format!("Pitch:{pitch_data}");
-    let reading_opt = if reading.is_empty() {
-        None
-    } else {
-        Some(reading)
-    };
-
-    (content, reading_opt)
+    (reading, pitches)
 }
 
 fn parse_ipa_meta(data_blob: &Value) -> (String, Option<String>) {
@@ -1455,7 +1485,69 @@ fn insert_encoded_term_rows(
     Ok(())
 }
 
+/// Removes the batches a failed import already committed, restoring the term indexes if the
+/// import had dropped them.
+fn discard_partial_import(
+    conn: &rusqlite::Connection,
+    dict_id: DictionaryId,
+    restore_indexes: bool,
+) {
+    for sql in DELETE_DICTIONARY_SQL {
+        if let Err(err) = conn.execute(sql, rusqlite::params![dict_id.0]) {
+            warn!(
+                "⚠️ [Import] Failed to discard partial dictionary {}: {}",
+                dict_id.0, err
+            );
+        }
+    }
+    if restore_indexes && let Err(err) = conn.execute_batch(CREATE_TERM_INDEXES_SQL) {
+        warn!("⚠️ [Import] Failed to restore term indexes: {}", err);
+    }
+}
+
 pub fn import_zip(state: &AppState, data: &[u8]) -> Result<String> {
+    import_archive(state, data, None).map(|(_, message)| message)
+}
+
+/// Imports `data` as a replacement for dictionary `replace`.
+///
+/// The new archive is staged under a fresh id and the old dictionary is removed in the same
+/// transaction, so a failed import leaves the old one untouched. The old `priority` and `enabled`
+/// settings carry over.
+pub fn import_zip_replacing(
+    state: &AppState,
+    data: &[u8],
+    replace: DictionaryId,
+) -> Result<String> {
+    import_archive(state, data, Some(replace)).map(|(_, message)| message)
+}
+
+/// [`import_zip`] or [`import_zip_replacing`], also returning the id the dictionary was stored
+/// under.
+pub(crate) fn import_archive(
+    state: &AppState,
+    data: &[u8],
+    replace: Option<DictionaryId>,
+) -> Result<(DictionaryId, String)> {
+    if let Some(replace) = replace
+        && !state
+            .dictionaries
+            .read()
+            .expect("lock")
+            .contains_key(&replace)
+    {
+        return Err(anyhow!("Dictionary {} not found.", replace.0));
+    }
+    import_zip_inner(state, data, replace)
+}
+
+fn import_zip_inner(
+    state: &AppState,
+    data: &[u8],
+    replace: Option<DictionaryId>,
+) -> Result<(DictionaryId, String)> {
+    let _running = state.begin_import();
+    let _writer = state.lock_import_writer();
     if data.len() > MAX_IMPORT_ARCHIVE_BYTES {
         return Err(anyhow!(
             "Archive is too large ({} bytes, max {MAX_IMPORT_ARCHIVE_BYTES}).",
@@ -1546,10 +1638,9 @@ pub fn import_zip(state: &AppState, data: &[u8]) -> Result<String> {
     let normalized_name = dict_name.trim().to_lowercase();
     {
         let dicts = state.dictionaries.read().expect("lock");
-        if dicts
-            .values()
-            .any(|dict| dict.name.trim().to_lowercase() == normalized_name)
-        {
+        if dicts.values().any(|dict| {
+            Some(dict.id) != replace && dict.name.trim().to_lowercase() == normalized_name
+        }) {
             return Err(anyhow!(format!(
                 "Dictionary '{dict_name}' is already imported."
             )));
@@ -1567,520 +1658,568 @@ pub fn import_zip(state: &AppState, data: &[u8]) -> Result<String> {
              PRAGMA locking_mode = EXCLUSIVE;",
         );
     }
-    let tx = conn.transaction()?;
-    if defer_term_indexes {
-        tx.execute_batch(
-            "DROP INDEX IF EXISTS idx_term;
-             DROP INDEX IF EXISTS idx_reading;
-             DROP INDEX IF EXISTS idx_dict_term;
-             DROP INDEX IF EXISTS idx_term_dict;
-             DROP INDEX IF EXISTS idx_reading_dict;",
-        )?;
-    }
-
-    // 3. Register Dictionary in DB.
-    // If next_dict_id is stale versus on-disk state, retry on unique-id conflicts.
+    state.reset_import_progress();
+    let commit_rows = state.import_commit_rows();
     let mut dict_id = DictionaryId(0);
-    let mut inserted = false;
-    {
-        let mut next_id = state.next_dict_id.write().expect("lock");
-        for _ in 0..1024 {
-            dict_id = DictionaryId(*next_id);
-            *next_id += 1;
-            match tx.execute(
-                "INSERT INTO dictionaries (id, name, priority, enabled) VALUES (?, ?, ?, ?)",
-                rusqlite::params![dict_id.0, dict_name, 0, true],
-            ) {
-                Ok(_) => {
-                    inserted = true;
-                    break;
-                }
-                Err(err)
-                    if err
-                        .to_string()
-                        .contains("UNIQUE constraint failed: dictionaries.id") =>
-                {
-                    warn!(
-                        "Dictionary id {} already exists during import; retrying with next id",
-                        dict_id.0
-                    );
+    let mut checkpointed = false;
+    let mut terms_found = 0usize;
+    // Everything up to the final commit runs here, so a failure after an intermediate commit
+    // still reaches the cleanup below.
+    let written = (|| -> Result<(i64, bool)> {
+        let mut tx = conn.transaction()?;
+        if defer_term_indexes {
+            tx.execute_batch(
+                "DROP INDEX IF EXISTS idx_term;
+                 DROP INDEX IF EXISTS idx_reading;
+                 DROP INDEX IF EXISTS idx_dict_term;
+                 DROP INDEX IF EXISTS idx_term_dict;
+                 DROP INDEX IF EXISTS idx_reading_dict;",
+            )?;
+        }
+
+        // 3. Register Dictionary in DB.
+        // If next_dict_id is stale versus on-disk state, retry on unique-id conflicts.
+        let mut inserted = false;
+        {
+            let mut next_id = state.next_dict_id.write().expect("lock");
+            for _ in 0..1024 {
+                dict_id = DictionaryId(*next_id);
+                *next_id += 1;
+                match tx.execute(
+                    "INSERT INTO dictionaries (id, name, priority, enabled, import_complete)
+                     VALUES (?, ?, ?, ?, 0)",
+                    rusqlite::params![dict_id.0, dict_name, 0, false],
+                ) {
+                    Ok(_) => {
+                        inserted = true;
+                        break;
+                    }
+                    Err(err)
+                        if err
+                            .to_string()
+                            .contains("UNIQUE constraint failed: dictionaries.id") =>
+                    {
+                        warn!(
+                            "Dictionary id {} already exists during import; retrying with next id",
+                            dict_id.0
+                        );
+                    }
+                    Err(err) => return Err(anyhow!(err)),
                 }
-                Err(err) => return Err(anyhow!(err)),
             }
         }
-    }
-    if !inserted {
-        return Err(anyhow!(
-            "Failed to allocate dictionary id after repeated conflicts."
-        ));
-    }
+        if !inserted {
+            return Err(anyhow!(
+                "Failed to allocate dictionary id after repeated conflicts."
+            ));
+        }
 
-    // 3.5. Collect archive entries once, then optionally extract media.
-    let file_names: Vec<String> = (0..zip.len())
-        .filter_map(|i| zip.by_index(i).ok().map(|f| f.name().to_string()))
-        .collect();
-    let has_media_entries = file_names.iter().any(|file_name| {
-        !file_name.ends_with(".json")
-            && !file_name.ends_with(".json.gz")
-            && !file_name.contains("index")
-            && !file_name.contains("meta")
-            && !file_name.ends_with("styles.css")
-    });
+        // 3.5. Collect archive entries once, then optionally extract media.
+        let file_names: Vec<String> = (0..zip.len())
+            .filter_map(|i| zip.by_index(i).ok().map(|f| f.name().to_string()))
+            .collect();
+        let has_media_entries = file_names.iter().any(|file_name| {
+            !file_name.ends_with(".json")
+                && !file_name.ends_with(".json.gz")
+                && !file_name.contains("index")
+                && !file_name.contains("meta")
+                && !file_name.ends_with("styles.css")
+        });
 
-    let mut styles_content: Option<String> = None;
+        let mut styles_content: Option<String> = None;
 
-    if let Some(styles_name) = file_names
-        .iter()
-        .find(|name| name.ends_with("styles.css"))
-        .cloned()
-        && let Some(mut file) = open_zip_file_safe(&mut zip, &styles_name)
-    {
-        let mut contents = String::new();
-        if file.read_to_string(&mut contents).is_ok() {
-            styles_content = Some(contents);
+        if let Some(styles_name) = file_names
+            .iter()
+            .find(|name| name.ends_with("styles.css"))
+            .cloned()
+            && let Some(mut file) = open_zip_file_safe(&mut zip, &styles_name)
+        {
+            let mut contents = String::new();
+            if file.read_to_string(&mut contents).is_ok() {
+                styles_content = Some(contents);
+            }
         }
-    }
-
-    let dict_media_dir = state.data_dir.join("dict_media").join(&dict_name);
-    if !skip_media {
-        fs::create_dir_all(&dict_media_dir)?;
 
-        let mut media_files_extracted = 0usize;
-        let mut created_media_dirs = HashSet::new();
+        let dict_media_dir = state.data_dir.join("dict_media").join(&dict_name);
+        if !skip_media {
+            fs::create_dir_all(&dict_media_dir)?;
 
-        for file_name in &file_names {
-            if file_name.ends_with(".json")
-                || file_name.ends_with(".json.gz")
-                || file_name.contains("index")
-                || file_name.contains("meta")
-                || file_name.ends_with("styles.css")
-            {
-                continue;
-            }
+            let mut media_files_extracted = 0usize;
+            let mut created_media_dirs = HashSet::new();
 
-            let Some(mut file) = open_zip_file_safe(&mut zip, file_name) else {
-                continue;
-            };
-            let Some(media_path) = safe_join_path(&dict_media_dir, file_name) else {
-                continue;
-            };
+            for file_name in &file_names {
+                if file_name.ends_with(".json")
+                    || file_name.ends_with(".json.gz")
+                    || file_name.contains("index")
+                    || file_name.contains("meta")
+                    || file_name.ends_with("styles.css")
+                {
+                    continue;
+                }
 
-            if let Some(parent) = media_path.parent() {
-                let parent_key = parent.to_string_lossy().into_owned();
-                if created_media_dirs.insert(parent_key) && fs::create_dir_all(parent).is_err() {
+                let Some(mut file) = open_zip_file_safe(&mut zip, file_name) else {
                     continue;
+                };
+                let Some(media_path) = safe_join_path(&dict_media_dir, file_name) else {
+                    continue;
+                };
+
+                if let Some(parent) = media_path.parent() {
+                    let parent_key = parent.to_string_lossy().into_owned();
+                    if created_media_dirs.insert(parent_key) && fs::create_dir_all(parent).is_err()
+                    {
+                        continue;
+                    }
+                }
+
+                if let Ok(mut out) = fs::File::create(&media_path)
+                    && std::io::copy(&mut file, &mut out).is_ok()
+                {
+                    media_files_extracted += 1;
                 }
             }
 
-            if let Ok(mut out) = fs::File::create(&media_path)
-                && std::io::copy(&mut file, &mut out).is_ok()
-            {
-                media_files_extracted += 1;
+            if media_files_extracted > 0 {
+                info!(
+                    "      Extracted {} media files for '{}'",
+                    media_files_extracted, dict_name
+                );
             }
+        } else {
+            info!("      Skipped media extraction for '{}'", dict_name);
         }
 
-        if media_files_extracted > 0 {
-            info!(
-                "      Extracted {} media files for '{}'",
-                media_files_extracted, dict_name
-            );
+        // Update dictionary with styles
+        if let Some(styles) = styles_content {
+            tx.execute(
+                "UPDATE dictionaries SET styles = ? WHERE id = ?",
+                rusqlite::params![styles, dict_id.0],
+            )?;
+            let mut dicts = state.dictionaries.write().expect("lock");
+            if let Some(d) = dicts.get_mut(&dict_id) {
+                d.styles = Some(styles);
+            }
         }
-    } else {
-        info!("      Skipped media extraction for '{}'", dict_name);
-    }
 
-    // Update dictionary with styles
-    if let Some(styles) = styles_content {
-        tx.execute(
-            "UPDATE dictionaries SET styles = ? WHERE id = ?",
-            rusqlite::params![styles, dict_id.0],
-        )?;
-        let mut dicts = state.dictionaries.write().expect("lock");
-        if let Some(d) = dicts.get_mut(&dict_id) {
-            d.styles = Some(styles);
-        }
-    }
+        // 4. Scan for term banks and insert
+        let mut rows_at_checkpoint = 0usize;
+        let mut encoder = snap::raw::Encoder::new();
 
-    // 4. Scan for term banks and insert
-    let mut terms_found = 0usize;
-    let mut encoder = snap::raw::Encoder::new();
+        for name in &file_names {
+            // Dropping `tx` on this early return rolls back the open batch; committed ones are
+            // discarded by the caller.
+            if state.is_import_cancelled() {
+                info!("🛑 [Import] Cancelled before '{}'", name);
+                return Err(anyhow!("Import cancelled."));
+            }
 
-    for name in &file_names {
-        if name.contains("term_bank") && !name.contains("term_meta") && name.ends_with(".json") {
-            info!("   -> Processing definitions: {}", name);
+            // Commit at bank boundaries once enough rows are pending, so huge dictionaries never
+            // hold more than about one batch plus one bank in an open transaction.
+            if terms_found - rows_at_checkpoint >= commit_rows {
+                tx.commit()?;
+                tx = conn.transaction()?;
+                checkpointed = true;
+                rows_at_checkpoint = terms_found;
+                state.record_import_batch(terms_found);
+                info!("💾 [Import] Committed batch ({} rows so far)", terms_found);
+            }
 
-            let parse_result = (|| -> Result<usize> {
-                let mut file = match open_zip_file_safe(&mut zip, name) {
-                    Some(f) => f,
-                    None => return Ok(0),
-                };
+            if name.contains("term_bank") && !name.contains("term_meta") && name.ends_with(".json")
+            {
+                info!("   -> Processing definitions: {}", name);
 
-                let term_batch_size = env_usize("YOMITAN_IMPORTER_TERM_BATCH_SIZE")
-                    .unwrap_or(4096)
-                    .max(1);
-                let available_workers = std::thread::available_parallelism()
-                    .map(|count| count.get())
-                    .unwrap_or(1);
-                let term_encode_workers = env_usize("YOMITAN_IMPORTER_TERM_ENCODE_WORKERS")
-                    .unwrap_or(available_workers.min(8))
-                    .clamp(1, available_workers);
-                let mut pending_rows = Vec::with_capacity(term_batch_size);
-                let mut file_bytes = Vec::new();
-                file.read_to_end(&mut file_bytes)?;
-
-                let rows = parse_json_array_slice::<TermBankRow, _>(&file_bytes, |row| {
-                    if row.headword.is_empty() {
-                        return Ok(());
-                    }
+                let parse_result = (|| -> Result<usize> {
+                    let mut file = match open_zip_file_safe(&mut zip, name) {
+                        Some(f) => f,
+                        None => return Ok(0),
+                    };
 
-                    pending_rows.push(ParsedSerdeTermRow {
-                        headword: row.headword,
-                        reading: row.reading,
-                        definition_tags: row.definition_tags,
-                        popularity: row.popularity,
-                        definitions: row.definitions,
-                        term_tags: row.term_tags,
-                    });
-
-                    if pending_rows.len() >= term_batch_size {
-                        flush_serde_term_rows(
-                            &mut pending_rows,
-                            &tx,
-                            dict_id,
-                            no_compress,
-                            term_encode_workers,
-                            &mut terms_found,
-                        )?;
-                    }
+                    let term_batch_size = env_usize("YOMITAN_IMPORTER_TERM_BATCH_SIZE")
+                        .unwrap_or(4096)
+                        .max(1);
+                    let available_workers = std::thread::available_parallelism()
+                        .map(|count| count.get())
+                        .unwrap_or(1);
+                    let term_encode_workers = env_usize("YOMITAN_IMPORTER_TERM_ENCODE_WORKERS")
+                        .unwrap_or(available_workers.min(8))
+                        .clamp(1, available_workers);
+                    let mut pending_rows = Vec::with_capacity(term_batch_size);
+                    let mut file_bytes = Vec::new();
+                    file.read_to_end(&mut file_bytes)?;
+
+                    let rows = parse_json_array_slice::<TermBankRow, _>(&file_bytes, |row| {
+                        if row.headword.is_empty() {
+                            return Ok(());
+                        }
 
-                    Ok(())
-                })?;
+                        pending_rows.push(ParsedSerdeTermRow {
+                            headword: row.headword,
+                            reading: row.reading,
+                            definition_tags: row.definition_tags,
+                            popularity: row.popularity,
+                            definitions: row.definitions,
+                            term_tags: row.term_tags,
+                        });
+
+                        if pending_rows.len() >= term_batch_size {
+                            flush_serde_term_rows(
+                                &mut pending_rows,
+                                &tx,
+                                dict_id,
+                                no_compress,
+                                term_encode_workers,
+                                &mut terms_found,
+                            )?;
+                        }
 
-                flush_serde_term_rows(
-                    &mut pending_rows,
-                    &tx,
-                    dict_id,
-                    no_compress,
-                    term_encode_workers,
-                    &mut terms_found,
-                )?;
+                        Ok(())
+                    })?;
 
-                Ok(rows)
-            })();
+                    flush_serde_term_rows(
+                        &mut pending_rows,
+                        &tx,
+                        dict_id,
+                        no_compress,
+                        term_encode_workers,
+                        &mut terms_found,
+                    )?;
 
-            let rows = match parse_result {
-                Ok(count) => count,
-                Err(e) => {
-                    let error_str = format!("{e:?}");
-                    if error_str.contains("checksum")
-                        || error_str.contains("CRC")
-                        || error_str.contains("InvalidArchive")
-                    {
-                        warn!(
-                            "Term bank file had checksum error but data was read successfully: {}",
-                            name
-                        );
-                        continue;
-                    } else {
-                        return Err(e);
+                    Ok(rows)
+                })();
+
+                let rows = match parse_result {
+                    Ok(count) => count,
+                    Err(e) => {
+                        let error_str = format!("{e:?}");
+                        if error_str.contains("checksum")
+                            || error_str.contains("CRC")
+                            || error_str.contains("InvalidArchive")
+                        {
+                            warn!(
+                                "Term bank file had checksum error but data was read successfully: {}",
+                                name
+                            );
+                            continue;
+                        } else {
+                            return Err(e);
+                        }
                     }
-                }
-            };
+                };
 
-            if rows > 0 {
-                info!("      Parsed {} term rows from {}", rows, name);
+                if rows > 0 {
+                    info!("      Parsed {} term rows from {}", rows, name);
+                }
             }
-        }
-        // Branch 2: Metadata / frequencies / pitch / IPA (term_meta_bank)
-        else if name.contains("term_meta_bank") && name.ends_with(".json") {
-            info!("   -> Processing metadata: {}", name);
-
-            let parse_result = (|| -> Result<usize> {
-                let mut file = match open_zip_file_safe(&mut zip, name) {
-                    Some(f) => f,
-                    None => return Ok(0),
-                };
+            // Branch 2: Metadata / frequencies / pitch / IPA (term_meta_bank)
+            else if name.contains("term_meta_bank") && name.ends_with(".json") {
+                info!("   -> Processing metadata: {}", name);
 
-                let insert_batch_size = env_usize("YOMITAN_IMPORTER_TERM_INSERT_BATCH_SIZE")
-                    .unwrap_or(4096)
-                    .max(1);
-                let mut json_buffer = Vec::new();
-                let mut compressed_buffer = Vec::new();
-                let mut pending_inserts = Vec::with_capacity(insert_batch_size);
-                let mut file_bytes = Vec::new();
-                file.read_to_end(&mut file_bytes)?;
+                let parse_result = (|| -> Result<usize> {
+                    let mut file = match open_zip_file_safe(&mut zip, name) {
+                        Some(f) => f,
+                        None => return Ok(0),
+                    };
 
-                let rows = parse_json_array_slice::<TermMetaBankRow, _>(&file_bytes, |row| {
-                    if row.term.is_empty() || !["freq", "pitch", "ipa"].contains(&row.mode.as_str())
-                    {
-                        return Ok(());
-                    }
+                    let insert_batch_size = env_usize("YOMITAN_IMPORTER_TERM_INSERT_BATCH_SIZE")
+                        .unwrap_or(4096)
+                        .max(1);
+                    let mut json_buffer = Vec::new();
+                    let mut compressed_buffer = Vec::new();
+                    let mut pending_inserts = Vec::with_capacity(insert_batch_size);
+                    let mut freq_stmt = tx.prepare(
+                        "INSERT INTO term_frequencies (term, reading, dictionary_id, value) VALUES (?, ?, ?, ?)",
+                    )?;
+                    let mut pitch_stmt = tx.prepare(
+                        "INSERT INTO term_pitches (term, reading, dictionary_id, position, pattern, nasal, devoice, tags) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+                    )?;
+                    let mut file_bytes = Vec::new();
+                    file.read_to_end(&mut file_bytes)?;
+
+                    let rows = parse_json_array_slice::<TermMetaBankRow, _>(&file_bytes, |row| {
+                        if row.term.is_empty()
+                            || !["freq", "pitch", "ipa"].contains(&row.mode.as_str())
+                        {
+                            return Ok(());
+                        }
 
-                    let (content_str, specific_reading) = match row.mode.as_str() {
-                        "freq" => {
+                        // Frequencies get their own table rather than a `Frequency:` glossary row.
+                        if row.mode == "freq" {
                             let (display_val, reading) = parse_frequency_value(&row.data);
-                            let content = if let Some(ref r) = reading {
-                                if r != &row.term {
-                                    format!("Frequency: {display_val} ({r})")
-                                } else {
-                                    format!("Frequency: {display_val}")
-                                }
+                            freq_stmt.execute(rusqlite::params![
+                                row.term,
+                                reading,
+                                dict_id.0,
+                                display_val
+                            ])?;
+                            bump_term_count_by(&mut terms_found, 1)?;
+                            return Ok(());
+                        }
+
+                        // Pitch accents are stored one row per accent.
+                        if row.mode == "pitch" {
+                            let (reading, pitches) = parse_pitch_meta(&row.data);
+                            let reading = if reading.is_empty() {
+                                row.term.clone()
                             } else {
-                                format!("Frequency: {display_val}")
+                                reading
                             };
-                            (content, reading)
+                            for pitch in &pitches {
+                                pitch_stmt.execute(rusqlite::params![
+                                    row.term,
+                                    reading,
+                                    dict_id.0,
+                                    pitch.position,
+                                    pitch.pattern,
+                                    serde_json::to_string(&pitch.nasal)?,
+                                    serde_json::to_string(&pitch.devoice)?,
+                                    serde_json::to_string(&pitch.tags)?,
+                                ])?;
+                            }
+                            bump_term_count_by(&mut terms_found, pitches.len())?;
+                            return Ok(());
                         }
-                        "pitch" => parse_pitch_meta(&row.data),
-                        "ipa" => parse_ipa_meta(&row.data),
-                        _ => return Ok(()),
-                    };
-
-                    let term = row.term;
-                    let content_raw = serde_json::value::to_raw_value(&content_str)?;
-                    let compact = CompactGlossaryPayloadV1 {
-                        popularity: 0,
-                        content_raw: vec![content_raw],
-                        definition_tags_raw: None,
-                        term_tags_raw: None,
-                        reading: specific_reading.clone(),
-                        headword: Some(term.clone()),
-                    };
 
-                    encode_compact_glossary_payload(
-                        &compact,
-                        &mut encoder,
-                        &mut json_buffer,
-                        &mut compressed_buffer,
-                        no_compress,
-                    )?;
-
-                    pending_inserts.push(EncodedTermInsert {
-                        headword: term,
-                        reading: specific_reading,
-                        compressed: compressed_buffer.clone(),
-                    });
-                    if pending_inserts.len() >= insert_batch_size {
-                        insert_encoded_term_rows(
-                            &tx,
-                            std::mem::take(&mut pending_inserts),
-                            dict_id,
-                            &mut terms_found,
+                        let (content_str, specific_reading) = match row.mode.as_str() {
+                            "ipa" => parse_ipa_meta(&row.data),
+                            _ => return Ok(()),
+                        };
+
+                        let term = row.term;
+                        let content_raw = serde_json::value::to_raw_value(&content_str)?;
+                        let compact = CompactGlossaryPayloadV1 {
+                            popularity: 0,
+                            content_raw: vec![content_raw],
+                            definition_tags_raw: None,
+                            term_tags_raw: None,
+                            reading: specific_reading.clone(),
+                            headword: Some(term.clone()),
+                        };
+
+                        encode_compact_glossary_payload(
+                            &compact,
+                            &mut encoder,
+                            &mut json_buffer,
+                            &mut compressed_buffer,
+                            no_compress,
                         )?;
-                    }
-
-                    Ok(())
-                })?;
 
-                if !pending_inserts.is_empty() {
-                    insert_encoded_term_rows(&tx, pending_inserts, dict_id, &mut terms_found)?;
-                }
+                        pending_inserts.push(EncodedTermInsert {
+                            headword: term,
+                            reading: specific_reading,
+                            compressed: compressed_buffer.clone(),
+                        });
+                        if pending_inserts.len() >= insert_batch_size {
+                            insert_encoded_term_rows(
+                                &tx,
+                                std::mem::take(&mut pending_inserts),
+                                dict_id,
+                                &mut terms_found,
+                            )?;
+                        }
 
-                Ok(rows)
-            })();
+                        Ok(())
+                    })?;
 
-            let rows = match parse_result {
-                Ok(count) => count,
-                Err(e) => {
-                    let error_str = format!("{e:?}");
-                    if error_str.contains("checksum")
-                        || error_str.contains("CRC")
-                        || error_str.contains("InvalidArchive")
-                    {
-                        warn!(
-                            "Metadata file had checksum error but data was read successfully: {}",
-                            name
-                        );
-                        continue;
-                    } else {
-                        return Err(e);
+                    if !pending_inserts.is_empty() {
+                        insert_encoded_term_rows(&tx, pending_inserts, dict_id, &mut terms_found)?;
                     }
-                }
-            };
 
-            if rows > 0 {
-                info!("      Parsed {} metadata rows from {}", rows, name);
-            }
-        }
-        // Branch 3: Kanji bank (kanji_bank_*.json) - insert into terms table like pitch/freq
-        else if name.contains("kanji_bank") && name.ends_with(".json") {
-            let parse_result = (|| -> Result<usize> {
-                let mut file = match open_zip_file_safe(&mut zip, name) {
-                    Some(f) => f,
-                    None => return Ok(0),
+                    Ok(rows)
+                })();
+
+                let rows = match parse_result {
+                    Ok(count) => count,
+                    Err(e) => {
+                        let error_str = format!("{e:?}");
+                        if error_str.contains("checksum")
+                            || error_str.contains("CRC")
+                            || error_str.contains("InvalidArchive")
+                        {
+                            warn!(
+                                "Metadata file had checksum error but data was read successfully: {}",
+                                name
+                            );
+                            continue;
+                        } else {
+                            return Err(e);
+                        }
+                    }
                 };
 
-                let mut stmt = tx.prepare(
-                    "INSERT OR REPLACE INTO kanji (character, dictionary_id, onyomi, kunyomi, tags, meanings, stats) VALUES (?, ?, ?, ?, ?, ?, ?)"
-                )?;
+                if rows > 0 {
+                    info!("      Parsed {} metadata rows from {}", rows, name);
+                }
+            }
+            // Branch 3: Kanji bank (kanji_bank_*.json) - insert into terms table like pitch/freq
+            else if name.contains("kanji_bank") && name.ends_with(".json") {
+                let parse_result = (|| -> Result<usize> {
+                    let mut file = match open_zip_file_safe(&mut zip, name) {
+                        Some(f) => f,
+                        None => return Ok(0),
+                    };
 
-                let mut count = 0usize;
+                    let mut stmt = tx.prepare(
+                        "INSERT OR REPLACE INTO kanji (character, dictionary_id, onyomi, kunyomi, tags, meanings, stats) VALUES (?, ?, ?, ?, ?, ?, ?)"
+                    )?;
 
-                let _rows = parse_json_array_stream::<_, KanjiBankRow, _>(&mut file, |row| {
-                    if row.character.chars().count() != 1 {
-                        return Ok(());
-                    }
+                    let mut count = 0usize;
 
-                    let meanings_json = serde_json::to_string(&row.meanings).unwrap_or_default();
-                    let stats_json = if row.stats.is_object() {
-                        serde_json::to_string(&row.stats).unwrap_or_default()
-                    } else {
-                        String::new()
-                    };
+                    let _rows = parse_json_array_stream::<_, KanjiBankRow, _>(&mut file, |row| {
+                        if row.character.chars().count() != 1 {
+                            return Ok(());
+                        }
 
-                    stmt.execute(rusqlite::params![
-                        row.character,
-                        dict_id.0,
-                        row.onyomi,
-                        row.kunyomi,
-                        row.tags,
-                        meanings_json,
-                        stats_json
-                    ])?;
-
-                    count += 1;
-                    Ok(())
-                })?;
-
-                Ok(count)
-            })();
-
-            let rows = match parse_result {
-                Ok(count) => count,
-                Err(e) => {
-                    let error_str = format!("{e:?}");
-                    if error_str.contains("checksum")
-                        || error_str.contains("CRC")
-                        || error_str.contains("InvalidArchive")
-                    {
-                        warn!(
-                            "Kanji bank file had checksum error but data was read successfully: {}",
-                            name
-                        );
-                        continue;
-                    } else {
-                        return Err(e);
+                        let meanings_json =
+                            serde_json::to_string(&row.meanings).unwrap_or_default();
+                        let stats_json = if row.stats.is_object() {
+                            serde_json::to_string(&row.stats).unwrap_or_default()
+                        } else {
+                            String::new()
+                        };
+
+                        stmt.execute(rusqlite::params![
+                            row.character,
+                            dict_id.0,
+                            row.onyomi,
+                            row.kunyomi,
+                            row.tags,
+                            meanings_json,
+                            stats_json
+                        ])?;
+
+                        count += 1;
+                        Ok(())
+                    })?;
+
+                    Ok(count)
+                })();
+
+                let rows = match parse_result {
+                    Ok(count) => count,
+                    Err(e) => {
+                        let error_str = format!("{e:?}");
+                        if error_str.contains("checksum")
+                            || error_str.contains("CRC")
+                            || error_str.contains("InvalidArchive")
+                        {
+                            warn!(
+                                "Kanji bank file had checksum error but data was read successfully: {}",
+                                name
+                            );
+                            continue;
+                        } else {
+                            return Err(e);
+                        }
                     }
-                }
-            };
-
-            if rows > 0 {
-                info!("      Parsed {} kanji from {}", rows, name);
-            }
-        }
-        // Branch 4: Kanji metadata (kanji_meta_bank_*.json) - insert as freq like term_meta
-        else if name.contains("kanji_meta_bank") && name.ends_with(".json") {
-            info!("   -> Processing kanji metadata: {}", name);
-
-            let parse_result = (|| -> Result<usize> {
-                let mut file = match open_zip_file_safe(&mut zip, name) {
-                    Some(f) => f,
-                    None => return Ok(0),
                 };
 
-                let insert_batch_size = env_usize("YOMITAN_IMPORTER_TERM_INSERT_BATCH_SIZE")
-                    .unwrap_or(4096)
-                    .max(1);
-                let mut json_buffer = Vec::new();
-                let mut compressed_buffer = Vec::new();
-                let mut pending_inserts = Vec::with_capacity(insert_batch_size);
-                let mut file_bytes = Vec::new();
-                file.read_to_end(&mut file_bytes)?;
-
-                let rows = parse_json_array_slice::<KanjiMetaBankRow, _>(&file_bytes, |row| {
-                    if row.character.is_empty() || row.meta_type != "freq" {
-                        return Ok(());
-                    }
+                if rows > 0 {
+                    info!("      Parsed {} kanji from {}", rows, name);
+                }
+            }
+            // Branch 4: Kanji metadata (kanji_meta_bank_*.json) - frequencies go to kanji_meta
+            else if name.contains("kanji_meta_bank") && name.ends_with(".json") {
+                info!("   -> Processing kanji metadata: {}", name);
 
-                    let (display_val, _) = parse_frequency_value(&row.data);
-                    let content = format!("Frequency: {display_val}");
-                    let character = row.character;
-                    let content_raw = serde_json::value::to_raw_value(&content)?;
-
-                    let compact = CompactGlossaryPayloadV1 {
-                        popularity: 0,
-                        content_raw: vec![content_raw],
-                        definition_tags_raw: None,
-                        term_tags_raw: None,
-                        reading: None,
-                        headword: Some(character.clone()),
+                let parse_result = (|| -> Result<usize> {
+                    let mut file = match open_zip_file_safe(&mut zip, name) {
+                        Some(f) => f,
+                        None => return Ok(0),
                     };
 
-                    encode_compact_glossary_payload(
-                        &compact,
-                        &mut encoder,
-                        &mut json_buffer,
-                        &mut compressed_buffer,
-                        no_compress,
+                    let mut stmt = tx.prepare(
+                        "INSERT OR REPLACE INTO kanji_meta (character, dictionary_id, meta_type, data) VALUES (?, ?, 'freq', ?)",
                     )?;
+                    let mut file_bytes = Vec::new();
+                    file.read_to_end(&mut file_bytes)?;
 
-                    pending_inserts.push(EncodedTermInsert {
-                        headword: character,
-                        reading: None,
-                        compressed: compressed_buffer.clone(),
-                    });
-                    if pending_inserts.len() >= insert_batch_size {
-                        insert_encoded_term_rows(
-                            &tx,
-                            std::mem::take(&mut pending_inserts),
-                            dict_id,
-                            &mut terms_found,
-                        )?;
-                    }
+                    let rows = parse_json_array_slice::<KanjiMetaBankRow, _>(&file_bytes, |row| {
+                        if row.character.is_empty() || row.meta_type != "freq" {
+                            return Ok(());
+                        }
 
-                    Ok(())
-                })?;
+                        let (display_val, _) = parse_frequency_value(&row.data);
+                        stmt.execute(rusqlite::params![row.character, dict_id.0, display_val])?;
+                        bump_term_count_by(&mut terms_found, 1)?;
+
+                        Ok(())
+                    })?;
+
+                    Ok(rows)
+                })();
+
+                let rows = match parse_result {
+                    Ok(count) => count,
+                    Err(e) => {
+                        let error_str = format!("{e:?}");
+                        if error_str.contains("checksum")
+                            || error_str.contains("CRC")
+                            || error_str.contains("InvalidArchive")
+                        {
+                            warn!(
+                                "Kanji metadata file had checksum error but data was read successfully: {}",
+                                name
+                            );
+                            continue;
+                        } else {
+                            return Err(e);
+                        }
+                    }
+                };
 
-                if !pending_inserts.is_empty() {
-                    insert_encoded_term_rows(&tx, pending_inserts, dict_id, &mut terms_found)?;
+                if rows > 0 {
+                    info!("      Parsed {} kanji metadata rows from {}", rows, name);
                 }
+            }
+        }
 
-                Ok(rows)
-            })();
-
-            let rows = match parse_result {
-                Ok(count) => count,
-                Err(e) => {
-                    let error_str = format!("{e:?}");
-                    if error_str.contains("checksum")
-                        || error_str.contains("CRC")
-                        || error_str.contains("InvalidArchive")
-                    {
-                        warn!(
-                            "Kanji metadata file had checksum error but data was read successfully: {}",
-                            name
-                        );
-                        continue;
-                    } else {
-                        return Err(e);
-                    }
-                }
-            };
+        if defer_term_indexes {
+            tx.execute_batch(CREATE_TERM_INDEXES_SQL)?;
+        }
 
-            if rows > 0 {
-                info!("      Parsed {} kanji metadata rows from {}", rows, name);
+        if skip_media && has_media_entries {
+            let archive_path = dict_archive_path(&state.data_dir, dict_id);
+            if let Some(parent) = archive_path.parent() {
+                fs::create_dir_all(parent)?;
             }
+            fs::write(&archive_path, data)?;
         }
-    }
 
-    if defer_term_indexes {
-        tx.execute_batch(
-            "CREATE INDEX IF NOT EXISTS idx_term ON terms(term);
-             CREATE INDEX IF NOT EXISTS idx_reading ON terms(reading);
-             CREATE INDEX IF NOT EXISTS idx_dict_term ON terms(dictionary_id);
-             CREATE INDEX IF NOT EXISTS idx_term_dict ON terms(term, dictionary_id);
-             CREATE INDEX IF NOT EXISTS idx_reading_dict ON terms(reading, dictionary_id);",
+        // Swap out the dictionary being replaced, carrying over its user settings.
+        let (priority, enabled) = match replace {
+            Some(old_id) => {
+                let settings: (i64, bool) = tx.query_row(
+                    "SELECT priority, enabled FROM dictionaries WHERE id = ?",
+                    rusqlite::params![old_id.0],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )?;
+                for sql in DELETE_DICTIONARY_SQL {
+                    tx.execute(sql, rusqlite::params![old_id.0])?;
+                }
+                settings
+            }
+            None => (0, true),
+        };
+        // The row stays disabled and incomplete until now, so a crash between batches never
+        // exposes a partial dictionary; `AppState::new` purges rows left incomplete.
+        tx.execute(
+            "UPDATE dictionaries SET priority = ?, enabled = ?, import_complete = 1 WHERE id = ?",
+            rusqlite::params![priority, enabled, dict_id.0],
         )?;
-    }
 
-    if skip_media && has_media_entries {
-        let archive_path = dict_archive_path(state, dict_id);
-        if let Some(parent) = archive_path.parent() {
-            fs::create_dir_all(parent)?;
+        tx.commit()?;
+        Ok((priority, enabled))
+    })();
+    let (priority, enabled) = match written {
+        Ok(settings) => settings,
+        Err(err) => {
+            if checkpointed {
+                discard_partial_import(&conn, dict_id, defer_term_indexes);
+            }
+            return Err(err);
         }
-        fs::write(&archive_path, data)?;
-    }
-
-    tx.commit()?;
+    };
+    state.record_import_batch(terms_found);
     info!(
         "💾 [Import] Database transaction committed. Total Terms: {}",
         terms_found
@@ -2095,44 +2234,37 @@ pub fn import_zip(state: &AppState, data: &[u8]) -> Result<String> {
     // Update in-memory dictionary registry only after a successful commit.
     {
         let mut dicts = state.dictionaries.write().expect("lock");
+        if let Some(old_id) = replace {
+            dicts.remove(&old_id);
+        }
         dicts.insert(
             dict_id,
             DictionaryData {
                 id: dict_id,
                 name: dict_name.clone(),
-                priority: 0,
-                enabled: true,
+                priority,
+                enabled,
                 styles: None,
             },
         );
     }
 
-    Ok(format!("Imported '{dict_name}'"))
+    if let Some(old_id) = replace {
+        let _ = fs::remove_file(dict_archive_path(&state.data_dir, old_id));
+        return Ok((dict_id, format!("Updated '{dict_name}'")));
+    }
+
+    Ok((dict_id, format!("Imported '{dict_name}'")))
 }
 
 #[cfg(test)]
 mod tests {
-    use std::{
-        fs,
-        io::Write,
-        path::PathBuf,
-        time::{SystemTime, UNIX_EPOCH},
-    };
+    use std::{fs, io::Write};
 
     use zip::{CompressionMethod, ZipWriter, write::SimpleFileOptions};
 
     use super::*;
-
-    fn test_data_dir(name: &str) -> PathBuf {
-        let nanos = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("time")
-            .as_nanos();
-        std::env::temp_dir().join(format!(
-            "manatan-yomitan-import-test-{name}-{}-{nanos}",
-            std::process::id()
-        ))
-    }
+    use crate::test_support::test_data_dir;
 
     fn build_zip(index_json: &str, entries: &[(&str, &str)]) -> Vec<u8> {
         let mut bytes = Vec::new();
@@ -2190,6 +2322,12 @@ mod tests {
 
             assert_eq!(dict_count, 1);
             assert_eq!(term_count, 1, "headword and reading share one stored row");
+            let import_complete: bool = conn
+                .query_row("SELECT import_complete FROM dictionaries", [], |row| {
+                    row.get(0)
+                })
+                .expect("import marker query");
+            assert!(import_complete);
 
             let decoded_payload = snap::raw::Decoder::new()
                 .decompress_vec(&stored_payload)
@@ -2273,6 +2411,421 @@ mod tests {
         assert!(captured.contains("\\x"));
     }
 
+    #[test]
+    fn update_preserves_priority_and_enabled() {
+        with_state("update-preserves-settings", |state| {
+            let v1 = build_zip(
+                r#"{"format":3,"title":"Update Dict","revision":"1"}"#,
+                &[(
+                    "term_bank_1.json",
+                    r#"[["猫","ねこ","",null,1,["cat"],0,""]]"#,
+                )],
+            );
+            let v2 = build_zip(
+                r#"{"format":3,"title":"Update Dict","revision":"2"}"#,
+                &[(
+                    "term_bank_1.json",
+                    r#"[["犬","いぬ","",null,1,["dog"],0,""]]"#,
+                )],
+            );
+
+            import_zip(state, &v1).expect("initial import should succeed");
+            let old_id = *state
+                .dictionaries
+                .read()
+                .expect("lock")
+                .keys()
+                .next()
+                .expect("dictionary registered");
+            {
+                let conn = state.pool.get().expect("db connection");
+                conn.execute(
+                    "UPDATE dictionaries SET priority = 7, enabled = 0 WHERE id = ?",
+                    [old_id.0],
+                )
+                .expect("update settings");
+                let mut dicts = state.dictionaries.write().expect("lock");
+                let dict = dicts.get_mut(&old_id).expect("dictionary registered");
+                dict.priority = 7;
+                dict.enabled = false;
+            }
+
+            let msg = import_zip_replacing(state, &v2, old_id).expect("update should succeed");
+            assert!(msg.contains("Updated 'Update Dict'"));
+
+            let dicts = state.dictionaries.read().expect("lock");
+            assert_eq!(dicts.len(), 1);
+            let updated = dicts.values().next().expect("dictionary registered");
+            assert_ne!(updated.id, old_id);
+            assert_eq!(updated.priority, 7);
+            assert!(!updated.enabled);
+
+            let conn = state.pool.get().expect("db connection");
+            let (priority, enabled): (i64, bool) = conn
+                .query_row(
+                    "SELECT priority, enabled FROM dictionaries WHERE id = ?",
+                    [updated.id.0],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .expect("updated dictionary row");
+            assert_eq!(priority, 7);
+            assert!(!enabled);
+
+            let terms: Vec<String> = conn
+                .prepare("SELECT term FROM terms")
+                .expect("prepare")
+                .query_map([], |row| row.get(0))
+                .expect("query")
+                .collect::<std::result::Result<_, _>>()
+                .expect("rows");
+            assert_eq!(terms, vec!["犬".to_string()]);
+        });
+    }
+
+    #[test]
+    fn concurrent_imports_are_serialized() {
+        with_state("import-concurrent", |state| {
+            let archives: Vec<Vec<u8>> = [("Dict A", "猫"), ("Dict B", "犬")]
+                .into_iter()
+                .map(|(title, term)| {
+                    let index = format!(r#"{{"format":3,"title":"{title}","revision":"1"}}"#);
+                    let bank = format!(r#"[["{term}","","",null,1,["x"],0,""]]"#);
+                    build_zip(&index, &[("term_bank_1.json", bank.as_str())])
+                })
+                .collect();
+
+            let handles: Vec<_> = archives
+                .into_iter()
+                .map(|bytes| {
+                    let state = state.clone();
+                    std::thread::spawn(move || import_zip(&state, &bytes))
+                })
+                .collect();
+            for handle in handles {
+                handle
+                    .join()
+                    .expect("import thread")
+                    .expect("import should succeed");
+            }
+
+            let dicts = state.dictionaries.read().expect("lock");
+            let mut ids: Vec<i64> = dicts.keys().map(|id| id.0).collect();
+            ids.sort_unstable();
+            ids.dedup();
+            assert_eq!(ids.len(), 2);
+
+            let conn = state.pool.get().expect("db connection");
+            let dict_count: i64 = conn
+                .query_row("SELECT COUNT(*) FROM dictionaries", [], |row| row.get(0))
+                .expect("dictionary count query");
+            let term_count: i64 = conn
+                .query_row("SELECT COUNT(*) FROM terms", [], |row| row.get(0))
+                .expect("term count query");
+            assert_eq!(dict_count, 2);
+            assert_eq!(term_count, 2);
+        });
+    }
+
+    #[test]
+    fn cancelled_import_rolls_back() {
+        with_state("import-cancel", |state| {
+            let first = build_zip(
+                r#"{"format":3,"title":"First Dict","revision":"1"}"#,
+                &[(
+                    "term_bank_1.json",
+                    r#"[["猫","ねこ","",null,1,["cat"],0,""]]"#,
+                )],
+            );
+            let second = build_zip(
+                r#"{"format":3,"title":"Second Dict","revision":"1"}"#,
+                &[
+                    (
+                        "term_bank_1.json",
+                        r#"[["犬","いぬ","",null,1,["dog"],0,""]]"#,
+                    ),
+                    (
+                        "term_bank_2.json",
+                        r#"[["鳥","とり","",null,1,["bird"],0,""]]"#,
+                    ),
+                ],
+            );
+            import_zip(state, &first).expect("first import should succeed");
+
+            // Stand in for a concurrent import so the flag stays set for the next one.
+            let running = state.begin_import();
+            state.request_import_cancel();
+            let err = import_zip(state, &second).expect_err("cancelled import should fail");
+            assert!(err.to_string().contains("cancelled"));
+
+            assert_eq!(state.dictionaries.read().expect("lock").len(), 1);
+            let conn = state.pool.get().expect("db connection");
+            let dict_count: i64 = conn
+                .query_row("SELECT COUNT(*) FROM dictionaries", [], |row| row.get(0))
+                .expect("dictionary count query");
+            let term_count: i64 = conn
+                .query_row("SELECT COUNT(*) FROM terms", [], |row| row.get(0))
+                .expect("term count query");
+            assert_eq!(dict_count, 1);
+            assert_eq!(term_count, 1);
+            drop(conn);
+
+            drop(running);
+            assert!(!state.is_import_cancelled());
+            import_zip(state, &second).expect("import after cancel should succeed");
+        });
+    }
+
+    #[test]
+    fn failed_update_keeps_old_dictionary() {
+        with_state("update-failure", |state| {
+            let v1 = build_zip(
+                r#"{"format":3,"title":"Keep Dict","revision":"1"}"#,
+                &[(
+                    "term_bank_1.json",
+                    r#"[["猫","ねこ","",null,1,["cat"],0,""]]"#,
+                )],
+            );
+            let broken = build_zip(
+                r#"{"format":3,"title":"Keep Dict","revision":"2"}"#,
+                &[(
+                    "term_bank_1.json",
+                    r#"[["犬","いぬ","",null,1,["dog"],0,""]"#,
+                )],
+            );
+
+            import_zip(state, &v1).expect("initial import should succeed");
+            let old_id = *state
+                .dictionaries
+                .read()
+                .expect("lock")
+                .keys()
+                .next()
+                .expect("dictionary registered");
+
+            import_zip_replacing(state, &broken, old_id).expect_err("broken update should fail");
+
+            let dicts = state.dictionaries.read().expect("lock");
+            assert!(dicts.contains_key(&old_id));
+            let conn = state.pool.get().expect("db connection");
+            let term_count: i64 = conn
+                .query_row(
+                    "SELECT COUNT(*) FROM terms WHERE dictionary_id = ?",
+                    [old_id.0],
+                    |row| row.get(0),
+                )
+                .expect("term count query");
+            assert_eq!(term_count, 1);
+        });
+    }
+
+    #[test]
+    fn imports_frequencies_into_structured_tables() {
+        with_state("structured-frequencies", |state| {
+            let zip = build_zip(
+                r#"{"format":3,"title":"Freq Dict","revision":"1"}"#,
+                &[
+                    (
+                        "term_meta_bank_1.json",
+                        r#"[["猫","freq",{"reading":"ねこ","frequency":120}],["犬","freq",42]]"#,
+                    ),
+                    ("kanji_meta_bank_1.json", r#"[["猫","freq",800]]"#),
+                ],
+            );
+
+            import_zip(state, &zip).expect("import should succeed");
+
+            let conn = state.pool.get().expect("db connection");
+            let mut rows: Vec<(String, Option<String>, String)> = conn
+                .prepare("SELECT term, reading, value FROM term_frequencies")
+                .expect("prepare")
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+                .expect("query")
+                .collect::<std::result::Result<_, _>>()
+                .expect("rows");
+            rows.sort();
+            assert_eq!(
+                rows,
+                vec![
+                    ("犬".to_string(), None, "42".to_string()),
+                    (
+                        "猫".to_string(),
+                        Some("ねこ".to_string()),
+                        "120".to_string()
+                    ),
+                ]
+            );
+
+            let kanji_freq: String = conn
+                .query_row(
+                    "SELECT data FROM kanji_meta WHERE character = '猫' AND meta_type = 'freq'",
+                    [],
+                    |row| row.get(0),
+                )
+                .expect("kanji frequency row");
+            assert_eq!(kanji_freq, "800");
+
+            let term_rows: i64 = conn
+                .query_row("SELECT COUNT(*) FROM terms", [], |row| row.get(0))
+                .expect("term count query");
+            assert_eq!(
+                term_rows, 0,
+                "frequencies must not be stored as glossary rows"
+            );
+        });
+    }
+
+    #[test]
+    fn parses_numeric_and_pattern_pitch_positions() {
+        let data: Value = serde_json::from_str(
+            r#"{"reading":"はし","pitches":[{"position":1,"nasal":2,"devoice":[1,3],"tags":["n"]},{"position":"LHH"},{"nasal":[1]}]}"#,
+        )
+        .expect("fixture json");
+
+        let (reading, pitches) = parse_pitch_meta(&data);
+        assert_eq!(reading, "はし");
+        assert_eq!(
+            pitches,
+            vec![
+                PitchMetaEntry {
+                    position: 1,
+                    pattern: String::new(),
+                    nasal: vec![2],
+                    devoice: vec![1, 3],
+                    tags: vec!["n".to_string()],
+                },
+                PitchMetaEntry {
+                    position: -1,
+                    pattern: "LHH".to_string(),
+                    nasal: vec![],
+                    devoice: vec![],
+                    tags: vec![],
+                },
+            ],
+            "entries without a position are dropped"
+        );
+    }
+
+    #[test]
+    fn imports_pitch_accents_into_structured_table() {
+        with_state("structured-pitch", |state| {
+            let zip = build_zip(
+                r#"{"format":3,"title":"Pitch Dict","revision":"1"}"#,
+                &[(
+                    "term_meta_bank_1.json",
+                    r#"[["箸","pitch",{"reading":"はし","pitches":[{"position":1},{"position":"HL","nasal":[2]}]}]]"#,
+                )],
+            );
+
+            import_zip(state, &zip).expect("import should succeed");
+
+            let conn = state.pool.get().expect("db connection");
+            let rows: Vec<(String, String, i64, String, String)> = conn
+                .prepare(
+                    "SELECT term, reading, position, pattern, nasal FROM term_pitches ORDER BY rowid",
+                )
+                .expect("prepare")
+                .query_map([], |row| {
+                    Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?))
+                })
+                .expect("query")
+                .collect::<std::result::Result<_, _>>()
+                .expect("rows");
+            assert_eq!(
+                rows,
+                vec![
+                    ("箸".into(), "はし".into(), 1, String::new(), "[]".into()),
+                    ("箸".into(), "はし".into(), -1, "HL".into(), "[2]".into()),
+                ]
+            );
+
+            let term_rows: i64 = conn
+                .query_row("SELECT COUNT(*) FROM terms", [], |row| row.get(0))
+                .expect("term count query");
+            assert_eq!(
+                term_rows, 0,
+                "pitch accents must not be stored as glossary rows"
+            );
+        });
+    }
+
+    #[test]
+    fn commits_multi_bank_import_in_batches() {
+        with_state("batched-commit", |state| {
+            state.set_import_commit_rows(2);
+            let zip = build_zip(
+                r#"{"format":3,"title":"Batched Dict","revision":"1"}"#,
+                &[
+                    (
+                        "term_bank_1.json",
+                        r#"[["猫","ねこ","",null,1,["cat"],0,""],["犬","いぬ","",null,1,["dog"],0,""]]"#,
+                    ),
+                    (
+                        "term_bank_2.json",
+                        r#"[["鳥","とり","",null,1,["bird"],0,""],["魚","さかな","",null,1,["fish"],0,""]]"#,
+                    ),
+                    (
+                        "term_bank_3.json",
+                        r#"[["馬","うま","",null,1,["horse"],0,""],["牛","うし","",null,1,["cow"],0,""]]"#,
+                    ),
+                ],
+            );
+
+            import_zip(state, &zip).expect("batched import should succeed");
+
+            let conn = state.pool.get().expect("db connection");
+            let term_count: i64 = conn
+                .query_row("SELECT COUNT(*) FROM terms", [], |row| row.get(0))
+                .expect("term count query");
+            assert_eq!(term_count, 6);
+
+            // Two intermediate commits at bank boundaries plus the final one.
+            let progress = state.import_progress();
+            assert_eq!(progress.batches_committed, 3);
+            assert_eq!(progress.rows_committed, 6);
+            assert!(
+                state
+                    .dictionaries
+                    .read()
+                    .expect("lock")
+                    .values()
+                    .all(|dict| dict.enabled)
+            );
+        });
+    }
+
+    #[test]
+    fn failed_batched_import_discards_committed_batches() {
+        with_state("batched-rollback", |state| {
+            state.set_import_commit_rows(1);
+            let zip = build_zip(
+                r#"{"format":3,"title":"Half Dict","revision":"1"}"#,
+                &[
+                    (
+                        "term_bank_1.json",
+                        r#"[["猫","ねこ","",null,1,["cat"],0,""]]"#,
+                    ),
+                    (
+                        "term_bank_2.json",
+                        r#"[["犬","いぬ","",null,1,["dog"],0,""]"#,
+                    ),
+                ],
+            );
+
+            import_zip(state, &zip).expect_err("truncated bank should fail");
+            assert_eq!(state.import_progress().batches_committed, 1);
+
+            let conn = state.pool.get().expect("db connection");
+            let dict_count: i64 = conn
+                .query_row("SELECT COUNT(*) FROM dictionaries", [], |row| row.get(0))
+                .expect("dict count query");
+            let term_count: i64 = conn
+                .query_row("SELECT COUNT(*) FROM terms", [], |row| row.get(0))
+                .expect("term count query");
+            assert_eq!(dict_count, 0);
+            assert_eq!(term_count, 0);
+        });
+    }
+
     #[test]
     fn failed_import_rolls_back_dictionary_row() {
         with_state("failed-import-rollback", |state| {
@@ -1,4 +1,7 @@
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use axum::{
     Router,
@@ -12,11 +15,15 @@ pub mod handlers;
 pub mod import;
 pub mod lookup;
 pub mod state;
+#[cfg(test)]
+mod test_support;
 
 use handlers::{
-    audio_handler, dict_media_handler, import_handler, install_defaults_handler,
-    install_language_handler, list_dictionaries_handler, lookup_handler,
-    manage_dictionaries_handler, reset_db_handler, unload_handler,
+    audio_any_handler, audio_handler, cancel_import_handler, dict_media_handler, import_handler,
+    install_defaults_handler, install_language_handler, install_languages_handler,
+    languages_handler, list_dictionaries_handler, lookup_route_handler,
+    manage_dictionaries_handler, reset_db_handler, status_handler, unload_handler,
+    update_dictionary_handler, version_handler,
 };
 use lookup::LookupService;
 use state::AppState;
@@ -27,27 +34,128 @@ pub struct ServerState {
     pub lookup: Arc<LookupService>,
 }
 
+/// Subdirectory of the shared data dir holding everything this server writes, like `sync/` and
+/// `novel/` for their servers.
+pub const STORAGE_DIR_NAME: &str = "yomitan";
+
+/// Entries older builds wrote straight into the shared data dir.
+const LEGACY_ROOT_ENTRIES: [&str; 4] = [
+    "yomitan.db",
+    "yomitan.db-journal",
+    "dict_media",
+    "dict_archives",
+];
+
+/// Returns `data_dir/yomitan`, moving files left in the shared root by older builds into it.
+pub fn storage_dir(data_dir: &Path) -> PathBuf {
+    manatan_storage::namespaced_dir(data_dir, STORAGE_DIR_NAME, &LEGACY_ROOT_ENTRIES)
+}
+
 pub fn create_router(data_dir: PathBuf) -> Router {
     let state = ServerState {
-        app: AppState::new(data_dir),
+        app: AppState::new(storage_dir(&data_dir)),
         lookup: Arc::new(LookupService::new()),
     };
+    handlers::seed_preferred_language(
+        &state.app,
+        std::env::var("MANATAN_STUDY_LANGUAGE").ok().as_deref(),
+    );
 
     let limit = 1024 * 1024 * 1024;
 
     Router::new()
-        .route("/lookup", get(lookup_handler))
+        .route("/lookup", get(lookup_route_handler))
         .route("/audio", get(audio_handler))
+        .route("/audio/any", get(audio_any_handler))
         .route("/dictionaries", get(list_dictionaries_handler))
         .route("/dict-media/{dict_name}/{*path}", get(dict_media_handler))
         .route("/import", post(import_handler))
+        .route("/import/cancel", post(cancel_import_handler))
         .route("/reset", post(reset_db_handler))
         .route("/manage", post(manage_dictionaries_handler))
         .route("/install-defaults", post(install_defaults_handler))
         .route("/install-language", post(install_language_handler))
+        .route("/install-languages", post(install_languages_handler))
+        .route("/update-dictionary", post(update_dictionary_handler))
         .route("/unload", post(unload_handler))
+        .route("/languages", get(languages_handler))
+        .route("/version", get(version_handler))
+        .route("/status", get(status_handler))
         .layer(CorsLayer::permissive())
         .layer(DefaultBodyLimit::max(limit))
         .layer(RequestBodyLimitLayer::new(limit))
         .with_state(state)
 }
+
+/// Number of dictionaries recorded in the database under `data_dir`, read without building a
+/// full `AppState`. A missing or unreadable database counts as empty.
+pub fn installed_dictionary_count(data_dir: &Path) -> usize {
+    let Some(db_path) = [
+        data_dir.join(STORAGE_DIR_NAME).join("yomitan.db"),
+        data_dir.join("yomitan.db"),
+    ]
+    .into_iter()
+    .find(|path| path.exists()) else {
+        return 0;
+    };
+    rusqlite::Connection::open_with_flags(&db_path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .and_then(|conn| {
+            conn.query_row("SELECT COUNT(*) FROM dictionaries", [], |row| {
+                row.get::<_, i64>(0)
+            })
+        })
+        .map(|count| usize::try_from(count).unwrap_or(0))
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{Body, to_bytes},
+        http::{Request, StatusCode},
+    };
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn version_route_reports_crate_version() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let response = create_router(dir.path().to_path_buf())
+            .oneshot(
+                Request::builder()
+                    .uri("/version")
+                    .body(Body::empty())
+                    .expect("request"),
+            )
+            .await
+            .expect("response");
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let json: Value = serde_json::from_slice(&body).expect("json");
+        assert_eq!(json["name"], "manatan-yomitan-server");
+        assert_eq!(json["version"], env!("CARGO_PKG_VERSION"));
+    }
+
+    #[test]
+    fn storage_is_namespaced_and_legacy_files_move_in() {
+        let temp = tempfile::tempdir().expect("temp dir");
+        let dir = temp.path();
+        std::fs::create_dir_all(dir.join("dict_media").join("Old Dict")).expect("legacy media");
+
+        let storage = storage_dir(dir);
+        let app = AppState::new(storage.clone());
+        drop(app);
+
+        assert_eq!(storage, dir.join(STORAGE_DIR_NAME));
+        assert!(storage.join("yomitan.db").exists());
+        assert!(storage.join("dict_media").join("Old Dict").exists());
+        assert!(!dir.join("yomitan.db").exists());
+        assert!(!dir.join("dict_media").exists());
+        assert_eq!(installed_dictionary_count(dir), 0);
+    }
+}
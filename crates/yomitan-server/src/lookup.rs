@@ -35,6 +35,37 @@ pub struct KanjiFrequency {
     pub value: String,
 }
 
+/// A row from the `term_frequencies` table.
+#[derive(Debug, Clone)]
+pub struct TermFrequency {
+    pub term: String,
+    pub reading: Option<String>,
+    pub dictionary_id: DictionaryId,
+    pub value: String,
+}
+
+/// A row from the `term_pitches` table.
+#[derive(Debug, Clone)]
+pub struct TermPitch {
+    pub term: String,
+    pub reading: String,
+    pub dictionary_id: DictionaryId,
+    pub position: i64,
+    pub pattern: String,
+    pub nasal: Vec<i64>,
+    pub devoice: Vec<i64>,
+    pub tags: Vec<String>,
+}
+
+/// Headword and reading of `term`, for tie-breaking sorts.
+fn term_sort_key(term: &Term) -> (String, String) {
+    match term {
+        Term::Full(headword, reading) => (headword.to_string(), reading.to_string()),
+        Term::Headword(headword) => (headword.to_string(), String::new()),
+        Term::Reading(reading) => (String::new(), reading.to_string()),
+    }
+}
+
 pub struct LookupService {
     deinflector: Deinflector,
 }
@@ -78,6 +109,10 @@ impl LookupService {
 
     pub fn unload_tokenizer(&self) {}
 
+    pub fn has_deinflector(&self, language: DeinflectLanguage) -> bool {
+        self.deinflector.has_rules(language)
+    }
+
     pub fn search(
         &self,
         state: &AppState,
@@ -147,71 +182,94 @@ impl LookupService {
                 }
                 processed_candidates.insert(candidate.word.clone());
 
-                let rows = stmt.query_map(rusqlite::params![candidate.word], |row| {
-                    let dict_id: i64 = row.get(0)?;
-                    let compressed: Vec<u8> = row.get(1)?;
-                    Ok((dict_id, compressed))
-                });
+                Self::append_term_rows(
+                    &mut stmt,
+                    &candidate.word,
+                    candidate.source_len,
+                    &dict_configs,
+                    &mut decoder,
+                    &mut results,
+                );
+            }
+        }
 
-                if let Ok(mapped_rows) = rows {
-                    for (dict_id_raw, compressed_data) in mapped_rows.flatten() {
-                        let dict_id = DictionaryId(dict_id_raw);
+        Self::sort_term_results(&mut results, &dict_configs);
+        results
+    }
 
-                        if let Some((enabled, _)) = dict_configs.get(&dict_id)
-                            && !*enabled
-                        {
-                            continue;
-                        }
+    /// Decodes every `terms` row `stmt` yields for `word` from enabled dictionaries into
+    /// `results`, each spanning `match_len` characters of the input.
+    fn append_term_rows(
+        stmt: &mut rusqlite::Statement<'_>,
+        word: &str,
+        match_len: usize,
+        dict_configs: &HashMap<DictionaryId, (bool, i64)>,
+        decoder: &mut snap::raw::Decoder,
+        results: &mut Vec<(RecordEntry, Option<Vec<GlossaryTag>>)>,
+    ) {
+        let rows = stmt.query_map(rusqlite::params![word], |row| {
+            let dict_id: i64 = row.get(0)?;
+            let compressed: Vec<u8> = row.get(1)?;
+            Ok((dict_id, compressed))
+        });
 
-                        if let Ok(decompressed) = decoder.decompress_vec(&compressed_data)
-                            && let Some(mut stored) =
-                                Self::decode_stored_record_payload(&decompressed)
-                        {
-                            stored.dictionary_id = dict_id;
-                            let match_len = candidate.source_len;
-
-                            let headword = stored
-                                .headword
-                                .as_deref()
-                                .unwrap_or(candidate.word.as_str());
-                            let term_obj =
-                                Term::from_parts(Some(headword), stored.reading.as_deref())
-                                    .unwrap_or_else(|| {
-                                        Term::from_headword(headword.to_string())
-                                            .expect("headword should produce a valid term")
-                                    });
-
-                            let freq = if let Record::YomitanGlossary(g) = &stored.record {
-                                g.popularity
-                            } else {
-                                0
-                            };
-
-                            results.push((
-                                RecordEntry {
-                                    span_bytes: Span {
-                                        start: 0,
-                                        end: candidate.word.len() as u64,
-                                    },
-                                    span_chars: Span {
-                                        start: 0,
-                                        end: match_len as u64,
-                                    },
-                                    source: stored.dictionary_id,
-                                    term: term_obj,
-                                    record_id: RecordId(0),
-                                    record: stored.record.clone(),
-                                    profile_sorting_frequency: None,
-                                    source_sorting_frequency: Some(FrequencyValue::Rank(freq)),
-                                },
-                                stored.term_tags,
-                            ));
-                        }
-                    }
+        if let Ok(mapped_rows) = rows {
+            for (dict_id_raw, compressed_data) in mapped_rows.flatten() {
+                let dict_id = DictionaryId(dict_id_raw);
+
+                if let Some((enabled, _)) = dict_configs.get(&dict_id)
+                    && !*enabled
+                {
+                    continue;
+                }
+
+                if let Ok(decompressed) = decoder.decompress_vec(&compressed_data)
+                    && let Some(mut stored) = Self::decode_stored_record_payload(&decompressed)
+                {
+                    stored.dictionary_id = dict_id;
+
+                    let headword = stored.headword.as_deref().unwrap_or(word);
+                    let term_obj = Term::from_parts(Some(headword), stored.reading.as_deref())
+                        .unwrap_or_else(|| {
+                            Term::from_headword(headword.to_string())
+                                .expect("headword should produce a valid term")
+                        });
+
+                    let freq = if let Record::YomitanGlossary(g) = &stored.record {
+                        g.popularity
+                    } else {
+                        0
+                    };
+
+                    results.push((
+                        RecordEntry {
+                            span_bytes: Span {
+                                start: 0,
+                                end: word.len() as u64,
+                            },
+                            span_chars: Span {
+                                start: 0,
+                                end: match_len as u64,
+                            },
+                            source: stored.dictionary_id,
+                            term: term_obj,
+                            record_id: RecordId(0),
+                            record: stored.record.clone(),
+                            profile_sorting_frequency: None,
+                            source_sorting_frequency: Some(FrequencyValue::Rank(freq)),
+                        },
+                        stored.term_tags,
+                    ));
                 }
             }
         }
+    }
 
+    /// Longest match first, then dictionary priority, then popularity.
+    fn sort_term_results(
+        results: &mut [(RecordEntry, Option<Vec<GlossaryTag>>)],
+        dict_configs: &HashMap<DictionaryId, (bool, i64)>,
+    ) {
         results.sort_by(|a, b| {
             let len_cmp = b.0.span_chars.end.cmp(&a.0.span_chars.end);
             if len_cmp != std::cmp::Ordering::Equal {
@@ -239,10 +297,217 @@ impl LookupService {
                     None => 0,
                 }
             };
+            // Remaining ties fall back to the dictionary and the term itself, so equally ranked
+            // results come back in the same order on every lookup and `limit` cuts consistently.
             get_val(b.0.source_sorting_frequency.as_ref())
                 .cmp(&get_val(a.0.source_sorting_frequency.as_ref()))
+                .then_with(|| a.0.source.0.cmp(&b.0.source.0))
+                .then_with(|| term_sort_key(&a.0.term).cmp(&term_sort_key(&b.0.term)))
         });
+    }
+
+    /// Looks `text` up by reading, returning every headword that shares it. Kana input such as
+    /// `かみ` otherwise only matches entries whose headword is spelled in kana. Falls back to the
+    /// regular headword search when nothing has that reading.
+    pub fn search_by_reading(
+        &self,
+        state: &AppState,
+        text: &str,
+        language: DeinflectLanguage,
+    ) -> Vec<(RecordEntry, Option<Vec<GlossaryTag>>)> {
+        let reading = text.trim();
+        if reading.is_empty() {
+            return vec![];
+        }
+
+        let conn = match state.pool.get() {
+            Ok(c) => c,
+            Err(e) => {
+                error!("❌ Failed to get DB connection: {}", e);
+                return vec![];
+            }
+        };
+
+        let dict_configs: HashMap<DictionaryId, (bool, i64)> = {
+            let dicts = state.dictionaries.read().expect("lock");
+            dicts
+                .iter()
+                .map(|(id, d)| (*id, (d.enabled, d.priority)))
+                .collect()
+        };
+
+        // Kana-only entries often leave `reading` empty, so their headword is the reading.
+        let mut stmt = match conn.prepare(
+            "SELECT dictionary_id, json FROM terms
+             WHERE reading = ?1 OR (term = ?1 AND (reading IS NULL OR reading = ''))",
+        ) {
+            Ok(s) => s,
+            Err(e) => {
+                error!("❌ DB Prepare Error: {}", e);
+                return vec![];
+            }
+        };
+
+        let match_len = reading.chars().count();
+        let mut decoder = snap::raw::Decoder::new();
+        let mut results = Vec::new();
+        let mut variants = vec![reading.to_string()];
+        let hiragana = self.katakana_to_hiragana(reading);
+        if hiragana != reading {
+            variants.push(hiragana);
+        }
+        for variant in &variants {
+            Self::append_term_rows(
+                &mut stmt,
+                variant,
+                match_len,
+                &dict_configs,
+                &mut decoder,
+                &mut results,
+            );
+        }
+
+        if results.is_empty() {
+            drop(stmt);
+            drop(conn);
+            return self.search(state, reading, 0, language);
+        }
 
+        Self::sort_term_results(&mut results, &dict_configs);
+        results
+    }
+
+    /// Returns structured frequency rows for `terms` from enabled dictionaries, ordered by
+    /// dictionary priority.
+    pub fn search_frequencies(&self, state: &AppState, terms: &[String]) -> Vec<TermFrequency> {
+        let conn = match state.pool.get() {
+            Ok(c) => c,
+            Err(e) => {
+                error!("❌ Failed to get DB connection: {}", e);
+                return vec![];
+            }
+        };
+
+        let dict_configs: HashMap<DictionaryId, (bool, i64)> = {
+            let dicts = state.dictionaries.read().expect("lock");
+            dicts
+                .iter()
+                .map(|(id, d)| (*id, (d.enabled, d.priority)))
+                .collect()
+        };
+
+        let mut stmt = match conn
+            .prepare("SELECT reading, dictionary_id, value FROM term_frequencies WHERE term = ?")
+        {
+            Ok(s) => s,
+            Err(e) => {
+                error!("❌ DB Prepare Error: {}", e);
+                return vec![];
+            }
+        };
+
+        let mut results = Vec::new();
+        let mut seen = HashSet::new();
+        for term in terms {
+            if !seen.insert(term.as_str()) {
+                continue;
+            }
+            let rows = stmt.query_map(rusqlite::params![term], |row| {
+                Ok(TermFrequency {
+                    term: term.clone(),
+                    reading: row.get(0)?,
+                    dictionary_id: DictionaryId(row.get(1)?),
+                    value: row.get(2)?,
+                })
+            });
+            if let Ok(rows) = rows {
+                results.extend(rows.flatten().filter(|freq| {
+                    dict_configs
+                        .get(&freq.dictionary_id)
+                        .is_none_or(|(enabled, _)| *enabled)
+                }));
+            }
+        }
+
+        results.sort_by_key(|freq| {
+            dict_configs
+                .get(&freq.dictionary_id)
+                .map(|(_, p)| *p)
+                .unwrap_or(999)
+        });
+        results
+    }
+
+    /// Returns structured pitch accents for `terms` from enabled dictionaries, ordered by
+    /// dictionary priority and then import order.
+    pub fn search_pitches(&self, state: &AppState, terms: &[String]) -> Vec<TermPitch> {
+        let conn = match state.pool.get() {
+            Ok(c) => c,
+            Err(e) => {
+                error!("❌ Failed to get DB connection: {}", e);
+                return vec![];
+            }
+        };
+
+        let dict_configs: HashMap<DictionaryId, (bool, i64)> = {
+            let dicts = state.dictionaries.read().expect("lock");
+            dicts
+                .iter()
+                .map(|(id, d)| (*id, (d.enabled, d.priority)))
+                .collect()
+        };
+
+        let mut stmt = match conn.prepare(
+            "SELECT reading, dictionary_id, position, pattern, nasal, devoice, tags FROM term_pitches WHERE term = ? ORDER BY rowid",
+        ) {
+            Ok(s) => s,
+            Err(e) => {
+                error!("❌ DB Prepare Error: {}", e);
+                return vec![];
+            }
+        };
+
+        let parse_list = |raw: Option<String>| -> Vec<i64> {
+            raw.and_then(|s| serde_json::from_str(&s).ok())
+                .unwrap_or_default()
+        };
+
+        let mut results = Vec::new();
+        let mut seen = HashSet::new();
+        for term in terms {
+            if !seen.insert(term.as_str()) {
+                continue;
+            }
+            let rows = stmt.query_map(rusqlite::params![term], |row| {
+                Ok(TermPitch {
+                    term: term.clone(),
+                    reading: row.get(0)?,
+                    dictionary_id: DictionaryId(row.get(1)?),
+                    position: row.get(2)?,
+                    pattern: row.get(3)?,
+                    nasal: parse_list(row.get(4)?),
+                    devoice: parse_list(row.get(5)?),
+                    tags: row
+                        .get::<_, Option<String>>(6)?
+                        .and_then(|s| serde_json::from_str(&s).ok())
+                        .unwrap_or_default(),
+                })
+            });
+            if let Ok(rows) = rows {
+                results.extend(rows.flatten().filter(|pitch| {
+                    dict_configs
+                        .get(&pitch.dictionary_id)
+                        .is_none_or(|(enabled, _)| *enabled)
+                }));
+            }
+        }
+
+        results.sort_by_key(|pitch| {
+            dict_configs
+                .get(&pitch.dictionary_id)
+                .map(|(_, p)| *p)
+                .unwrap_or(999)
+        });
         results
     }
 
@@ -445,7 +710,7 @@ impl LookupService {
             };
 
             let mut meta_stmt = match conn.prepare(
-                "SELECT km.meta_type, km.data, d.name FROM kanji_meta km JOIN dictionaries d ON km.dictionary_id = d.id WHERE km.character = ?"
+                "SELECT km.meta_type, km.data, d.name FROM kanji_meta km JOIN dictionaries d ON km.dictionary_id = d.id WHERE km.character = ? AND d.enabled = 1"
             ) {
                 Ok(s) => s,
                 Err(_) => continue,
@@ -475,16 +740,19 @@ impl LookupService {
                 ))
             });
 
-            // Build frequencies map: dict_id -> Vec<(dict_name, value)>
-            let mut freq_map: std::collections::HashMap<i64, Vec<(String, String)>> =
-                std::collections::HashMap::new();
+            // Frequencies come from separate frequency dictionaries, so they apply to every
+            // kanji entry for this character.
+            let mut kanji_frequencies: Vec<KanjiFrequency> = Vec::new();
 
             if let Ok(meta_iter) = meta_result {
                 for (meta_type, data, dict_name) in meta_iter.flatten() {
                     if meta_type == "freq" {
                         // Parse frequency data - try JSON first, otherwise use as-is
                         let freq_value: String = serde_json::from_str(&data).unwrap_or(data);
-                        freq_map.entry(0).or_default().push((dict_name, freq_value));
+                        kanji_frequencies.push(KanjiFrequency {
+                            dictionary_name: dict_name,
+                            value: freq_value,
+                        });
                     }
                 }
             }
@@ -529,19 +797,6 @@ impl LookupService {
                 let stats: std::collections::HashMap<String, String> =
                     serde_json::from_str(&stats_json).unwrap_or_default();
 
-                // Get frequencies for this dictionary
-                let frequencies: Vec<KanjiFrequency> = freq_map
-                    .get(&dict_id.0)
-                    .map(|vec| {
-                        vec.iter()
-                            .map(|(dict_name, value)| KanjiFrequency {
-                                dictionary_name: dict_name.clone(),
-                                value: value.clone(),
-                            })
-                            .collect()
-                    })
-                    .unwrap_or_default();
-
                 results.push(KanjiEntry {
                     character: character.clone(),
                     dictionary_name: dict_name,
@@ -550,7 +805,7 @@ impl LookupService {
                     tags: tags_vec,
                     meanings,
                     stats,
-                    frequencies,
+                    frequencies: kanji_frequencies.clone(),
                     priority,
                 });
             }
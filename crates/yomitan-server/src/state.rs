@@ -1,9 +1,9 @@
 use std::{
-    collections::HashMap,
-    path::PathBuf,
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
     sync::{
-        Arc, RwLock,
-        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, MutexGuard, PoisonError, RwLock,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
     },
     time::{Duration, Instant},
 };
@@ -11,7 +11,7 @@ use std::{
 use r2d2::Pool;
 use r2d2_sqlite::SqliteConnectionManager;
 use serde::{Deserialize, Serialize};
-use tracing::info;
+use tracing::{info, warn};
 use wordbase_api::{DictionaryId, Record, dict::yomitan::GlossaryTag};
 
 pub type DbPool = Pool<SqliteConnectionManager>;
@@ -32,14 +32,69 @@ pub struct AppState {
     pub pool: DbPool,
     pub data_dir: PathBuf,
     pub loading: Arc<AtomicBool>,
+    import_cancel: Arc<AtomicBool>,
+    imports_running: Arc<AtomicUsize>,
+    import_writer: Arc<Mutex<()>>,
+    offline: Arc<AtomicBool>,
+    dict_downloads_allowed: Arc<AtomicBool>,
+    import_commit_rows: Arc<AtomicUsize>,
+    import_rows_committed: Arc<AtomicUsize>,
+    import_batches_committed: Arc<AtomicUsize>,
+    max_concurrent_downloads: usize,
+    download_permits: Arc<tokio::sync::Semaphore>,
+    active_downloads: Arc<Mutex<HashSet<PathBuf>>>,
     startup_instant: Instant,
 }
 
+/// How far the running import has got, counted in committed batches.
+#[derive(Clone, Copy, Serialize, Debug, Default, PartialEq, Eq)]
+pub struct ImportProgress {
+    pub rows_committed: usize,
+    pub batches_committed: usize,
+}
+
+/// Marks an import as running for its lifetime. The cancel flag is reset once the last running
+/// import finishes, so a cancel request never leaks into a later import.
+pub struct ImportRunGuard {
+    state: AppState,
+}
+
+/// Exclusive use of a partial download file, released on drop. Keeps two installs of the same
+/// archive from appending to one `.part` file.
+pub struct DownloadClaim {
+    state: AppState,
+    path: PathBuf,
+}
+
+impl Drop for DownloadClaim {
+    fn drop(&mut self) {
+        self.state
+            .active_downloads
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&self.path);
+    }
+}
+
+impl Drop for ImportRunGuard {
+    fn drop(&mut self) {
+        if self.state.imports_running.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.state.import_cancel.store(false, Ordering::SeqCst);
+        }
+    }
+}
+
 #[cfg(test)]
 const IMPORT_STARTUP_GUARD: Duration = Duration::from_millis(50);
 #[cfg(not(test))]
 const IMPORT_STARTUP_GUARD: Duration = Duration::from_secs(30);
 
+/// Rows an import may hold in one transaction before committing at the next bank boundary.
+const DEFAULT_IMPORT_COMMIT_ROWS: usize = 10_000;
+
+/// Dictionary archives downloaded at once; kept low so batch installs don't saturate mobile links.
+const DEFAULT_MAX_CONCURRENT_DOWNLOADS: usize = 1;
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct StoredRecord {
     pub dictionary_id: DictionaryId,
@@ -50,8 +105,62 @@ pub struct StoredRecord {
     pub headword: Option<String>,
 }
 
+/// Removes dictionaries whose import never reached its final commit, e.g. because the process
+/// died between batches, together with any archive staged for them.
+fn purge_incomplete_imports(conn: &rusqlite::Connection, data_dir: &Path) {
+    let ids: Vec<i64> = match conn.prepare("SELECT id FROM dictionaries WHERE import_complete = 0")
+    {
+        Ok(mut stmt) => match stmt.query_map([], |row| row.get::<_, i64>(0)) {
+            Ok(rows) => rows.flatten().collect(),
+            Err(err) => {
+                warn!("⚠️ [Yomitan] Failed to look up incomplete imports: {}", err);
+                return;
+            }
+        },
+        Err(err) => {
+            warn!("⚠️ [Yomitan] Failed to look up incomplete imports: {}", err);
+            return;
+        }
+    };
+
+    for id in ids {
+        let purged = conn.unchecked_transaction().and_then(|tx| {
+            for sql in crate::import::DELETE_DICTIONARY_SQL {
+                tx.execute(sql, rusqlite::params![id])?;
+            }
+            tx.commit()
+        });
+        match purged {
+            Ok(()) => {
+                info!("🧹 [Yomitan] Removed partially imported dictionary {}", id);
+                let _ = std::fs::remove_file(crate::import::dict_archive_path(
+                    data_dir,
+                    DictionaryId(id),
+                ));
+            }
+            Err(err) => warn!(
+                "⚠️ [Yomitan] Failed to remove partially imported dictionary {}: {}",
+                id, err
+            ),
+        }
+    }
+}
+
 impl AppState {
+    /// Opens the state under `data_dir`, allowing `YOMITAN_MAX_CONCURRENT_DOWNLOADS` (default 1)
+    /// dictionary downloads at once.
     pub fn new(data_dir: PathBuf) -> Self {
+        let max_concurrent_downloads = crate::import::env_usize("YOMITAN_MAX_CONCURRENT_DOWNLOADS")
+            .unwrap_or(DEFAULT_MAX_CONCURRENT_DOWNLOADS);
+        Self::with_max_concurrent_downloads(data_dir, max_concurrent_downloads)
+    }
+
+    /// Like [`AppState::new`], with an explicit dictionary download limit (at least 1).
+    pub fn with_max_concurrent_downloads(
+        data_dir: PathBuf,
+        max_concurrent_downloads: usize,
+    ) -> Self {
+        let max_concurrent_downloads = max_concurrent_downloads.max(1);
         if !data_dir.exists() {
             let _ = std::fs::create_dir_all(&data_dir);
         }
@@ -73,7 +182,8 @@ impl AppState {
                 name TEXT NOT NULL,
                 priority INTEGER DEFAULT 0,
                 enabled BOOLEAN DEFAULT 1,
-                styles TEXT
+                styles TEXT,
+                import_complete BOOLEAN DEFAULT 1
              );
 
              CREATE TABLE IF NOT EXISTS terms (
@@ -89,6 +199,30 @@ impl AppState {
              CREATE INDEX IF NOT EXISTS idx_term_dict ON terms(term, dictionary_id);
              CREATE INDEX IF NOT EXISTS idx_reading_dict ON terms(reading, dictionary_id);
              
+             CREATE TABLE IF NOT EXISTS term_frequencies (
+                term TEXT NOT NULL,
+                reading TEXT,
+                dictionary_id INTEGER NOT NULL,
+                value TEXT NOT NULL
+             );
+
+             CREATE INDEX IF NOT EXISTS idx_term_frequencies_term ON term_frequencies(term);
+             CREATE INDEX IF NOT EXISTS idx_term_frequencies_dict ON term_frequencies(dictionary_id);
+
+             CREATE TABLE IF NOT EXISTS term_pitches (
+                term TEXT NOT NULL,
+                reading TEXT NOT NULL,
+                dictionary_id INTEGER NOT NULL,
+                position INTEGER NOT NULL,
+                pattern TEXT NOT NULL DEFAULT '',
+                nasal TEXT,
+                devoice TEXT,
+                tags TEXT
+             );
+
+             CREATE INDEX IF NOT EXISTS idx_term_pitches_term ON term_pitches(term);
+             CREATE INDEX IF NOT EXISTS idx_term_pitches_dict ON term_pitches(dictionary_id);
+
              CREATE TABLE IF NOT EXISTS metadata (
                 key TEXT PRIMARY KEY,
                 value TEXT
@@ -98,6 +232,10 @@ impl AppState {
 
         // Migration: add columns/indexes for existing installs (ignore errors for existing columns).
         let _ = conn.execute("ALTER TABLE dictionaries ADD COLUMN styles TEXT", []);
+        let _ = conn.execute(
+            "ALTER TABLE dictionaries ADD COLUMN import_complete BOOLEAN DEFAULT 1",
+            [],
+        );
         let _ = conn.execute("ALTER TABLE terms ADD COLUMN reading TEXT", []);
         let _ = conn.execute_batch(
             "CREATE INDEX IF NOT EXISTS idx_reading ON terms(reading);
@@ -131,6 +269,8 @@ impl AppState {
         )
         .ok();
 
+        purge_incomplete_imports(&conn, &data_dir);
+
         // 2. Load Dictionaries from DB
         let mut dicts = HashMap::new();
         let mut max_id = 0;
@@ -170,10 +310,60 @@ impl AppState {
             pool,
             data_dir,
             loading: Arc::new(AtomicBool::new(false)),
+            import_cancel: Arc::new(AtomicBool::new(false)),
+            imports_running: Arc::new(AtomicUsize::new(0)),
+            import_writer: Arc::new(Mutex::new(())),
+            offline: Arc::new(AtomicBool::new(crate::import::env_flag("MANATAN_OFFLINE"))),
+            dict_downloads_allowed: Arc::new(AtomicBool::new(crate::import::env_flag_or(
+                "MANATAN_ALLOW_DICT_DOWNLOAD",
+                true,
+            ))),
+            import_commit_rows: Arc::new(AtomicUsize::new(
+                crate::import::env_usize("YOMITAN_IMPORTER_COMMIT_ROWS")
+                    .unwrap_or(DEFAULT_IMPORT_COMMIT_ROWS)
+                    .max(1),
+            )),
+            import_rows_committed: Arc::new(AtomicUsize::new(0)),
+            import_batches_committed: Arc::new(AtomicUsize::new(0)),
+            max_concurrent_downloads,
+            download_permits: Arc::new(tokio::sync::Semaphore::new(max_concurrent_downloads)),
+            active_downloads: Arc::new(Mutex::new(HashSet::new())),
             startup_instant: Instant::now(),
         }
     }
 
+    pub fn begin_import(&self) -> ImportRunGuard {
+        self.imports_running.fetch_add(1, Ordering::SeqCst);
+        ImportRunGuard {
+            state: self.clone(),
+        }
+    }
+
+    /// Serializes imports. Each import mutates `dictionaries` and `next_dict_id`, so a second
+    /// one (e.g. a double-tapped install) waits here until the first has committed.
+    pub fn lock_import_writer(&self) -> MutexGuard<'_, ()> {
+        if let Ok(guard) = self.import_writer.try_lock() {
+            return guard;
+        }
+        info!("⏳ [Import] Another import is in progress; waiting for it to finish...");
+        self.import_writer
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn is_import_running(&self) -> bool {
+        self.imports_running.load(Ordering::SeqCst) > 0
+    }
+
+    /// Asks running imports to stop at the next bank boundary and roll back.
+    pub fn request_import_cancel(&self) {
+        self.import_cancel.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_import_cancelled(&self) -> bool {
+        self.import_cancel.load(Ordering::SeqCst)
+    }
+
     pub fn set_loading(&self, val: bool) {
         self.loading.store(val, Ordering::SeqCst);
     }
@@ -182,6 +372,82 @@ impl AppState {
         self.loading.load(Ordering::Relaxed)
     }
 
+    /// Whether outbound fetches (audio sources, dictionary downloads) are disabled. Seeded from
+    /// `MANATAN_OFFLINE`.
+    pub fn is_offline(&self) -> bool {
+        self.offline.load(Ordering::Relaxed)
+    }
+
+    pub fn set_offline(&self, offline: bool) {
+        self.offline.store(offline, Ordering::Relaxed);
+    }
+
+    /// Whether dictionaries may be downloaded at all; when not, only uploads can install them.
+    /// Seeded from `MANATAN_ALLOW_DICT_DOWNLOAD` (default on).
+    pub fn dict_downloads_allowed(&self) -> bool {
+        self.dict_downloads_allowed.load(Ordering::Relaxed)
+    }
+
+    pub fn set_dict_downloads_allowed(&self, allowed: bool) {
+        self.dict_downloads_allowed
+            .store(allowed, Ordering::Relaxed);
+    }
+
+    /// Seeded from `YOMITAN_IMPORTER_COMMIT_ROWS` (default 10k).
+    pub fn import_commit_rows(&self) -> usize {
+        self.import_commit_rows.load(Ordering::Relaxed)
+    }
+
+    pub fn set_import_commit_rows(&self, rows: usize) {
+        self.import_commit_rows
+            .store(rows.max(1), Ordering::Relaxed);
+    }
+
+    /// Fixed at construction; see [`AppState::with_max_concurrent_downloads`].
+    pub fn max_concurrent_downloads(&self) -> usize {
+        self.max_concurrent_downloads
+    }
+
+    /// Waits for a dictionary download slot; the slot frees up when the permit is dropped.
+    pub async fn acquire_download_permit(&self) -> tokio::sync::OwnedSemaphorePermit {
+        self.download_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("download semaphore is never closed")
+    }
+
+    /// Claims `path` for a download, or `None` while another download is writing to it.
+    pub fn claim_download(&self, path: &Path) -> Option<DownloadClaim> {
+        let inserted = self
+            .active_downloads
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(path.to_path_buf());
+        inserted.then(|| DownloadClaim {
+            state: self.clone(),
+            path: path.to_path_buf(),
+        })
+    }
+
+    pub fn import_progress(&self) -> ImportProgress {
+        ImportProgress {
+            rows_committed: self.import_rows_committed.load(Ordering::SeqCst),
+            batches_committed: self.import_batches_committed.load(Ordering::SeqCst),
+        }
+    }
+
+    pub(crate) fn reset_import_progress(&self) {
+        self.import_rows_committed.store(0, Ordering::SeqCst);
+        self.import_batches_committed.store(0, Ordering::SeqCst);
+    }
+
+    pub(crate) fn record_import_batch(&self, rows_committed: usize) {
+        self.import_rows_committed
+            .store(rows_committed, Ordering::SeqCst);
+        self.import_batches_committed.fetch_add(1, Ordering::SeqCst);
+    }
+
     pub fn is_import_startup_guard_active(&self) -> bool {
         self.startup_instant.elapsed() < IMPORT_STARTUP_GUARD
     }
@@ -195,24 +461,12 @@ impl AppState {
 
 #[cfg(test)]
 mod tests {
-    use std::{
-        fs,
-        path::PathBuf,
-        time::{Duration, SystemTime, UNIX_EPOCH},
-    };
+    use std::{fs, time::Duration};
 
-    use super::AppState;
+    use wordbase_api::DictionaryId;
 
-    fn test_data_dir(name: &str) -> PathBuf {
-        let nanos = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("time")
-            .as_nanos();
-        std::env::temp_dir().join(format!(
-            "manatan-yomitan-state-test-{name}-{}-{nanos}",
-            std::process::id()
-        ))
-    }
+    use super::AppState;
+    use crate::test_support::test_data_dir;
 
     #[test]
     fn startup_guard_is_active_immediately() {
@@ -236,4 +490,45 @@ mod tests {
         drop(state);
         let _ = fs::remove_dir_all(dir);
     }
+
+    #[test]
+    fn startup_purges_dictionaries_left_incomplete_by_a_crash() {
+        let dir = test_data_dir("purge-incomplete");
+        let state = AppState::new(dir.clone());
+        {
+            let conn = state.pool.get().expect("conn");
+            conn.execute_batch(
+                "INSERT INTO dictionaries (id, name, priority, enabled) VALUES (1, 'Done', 0, 1);
+                 INSERT INTO terms (term, reading, dictionary_id, json) VALUES ('猫', 'ねこ', 1, x'00');
+                 INSERT INTO dictionaries (id, name, priority, enabled, import_complete)
+                     VALUES (2, 'Partial', 0, 0, 0);
+                 INSERT INTO terms (term, reading, dictionary_id, json) VALUES ('犬', 'いぬ', 2, x'00');",
+            )
+            .expect("seed dictionaries");
+        }
+        let archive = crate::import::dict_archive_path(&dir, DictionaryId(2));
+        fs::create_dir_all(archive.parent().expect("archive dir")).expect("create archive dir");
+        fs::write(&archive, b"zip").expect("write archive");
+        drop(state);
+
+        let state = AppState::new(dir.clone());
+        let dicts = state.dictionaries.read().expect("lock");
+        assert!(dicts.contains_key(&DictionaryId(1)));
+        assert!(!dicts.contains_key(&DictionaryId(2)));
+        drop(dicts);
+        let conn = state.pool.get().expect("conn");
+        let partial_terms: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM terms WHERE dictionary_id = 2",
+                [],
+                |row| row.get(0),
+            )
+            .expect("count terms");
+        assert_eq!(partial_terms, 0);
+        assert!(!archive.exists());
+
+        drop(conn);
+        drop(state);
+        let _ = fs::remove_dir_all(dir);
+    }
 }
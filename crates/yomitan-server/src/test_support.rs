@@ -0,0 +1,28 @@
+//! Fixtures shared by the unit tests of every module.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{ServerState, lookup::LookupService, state::AppState};
+
+/// A unique data dir under the system temp dir. It is not created; `AppState::new` does that.
+pub(crate) fn test_data_dir(name: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("time")
+        .as_nanos();
+    std::env::temp_dir().join(format!(
+        "manatan-yomitan-test-{name}-{}-{nanos}",
+        std::process::id()
+    ))
+}
+
+pub(crate) fn test_server_state(dir: &Path) -> ServerState {
+    ServerState {
+        app: AppState::new(dir.to_path_buf()),
+        lookup: Arc::new(LookupService::new()),
+    }
+}